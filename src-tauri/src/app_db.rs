@@ -0,0 +1,95 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sqlx::Executor;
+use tauri::{AppHandle, Manager};
+
+const APP_DATABASE_FILE: &str = "serphic.db";
+
+const APP_DATABASE_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS query_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id TEXT NOT NULL,
+    connection_name TEXT NOT NULL,
+    db_type TEXT NOT NULL,
+    database_name TEXT NOT NULL,
+    sql TEXT NOT NULL,
+    executed_at INTEGER NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    row_count INTEGER,
+    success INTEGER NOT NULL,
+    error TEXT
+);
+CREATE INDEX IF NOT EXISTS query_history_executed_at ON query_history (executed_at);
+CREATE INDEX IF NOT EXISTS query_history_connection ON query_history (connection_id, executed_at);
+
+CREATE TABLE IF NOT EXISTS snippets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    folder TEXT NOT NULL DEFAULT '',
+    description TEXT NOT NULL DEFAULT '',
+    sql TEXT NOT NULL,
+    tags TEXT NOT NULL DEFAULT '[]',
+    default_connection_id TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+CREATE UNIQUE INDEX IF NOT EXISTS snippets_folder_name ON snippets (folder, name);
+CREATE VIRTUAL TABLE IF NOT EXISTS snippets_fts USING fts5(
+    name, description, sql, tags, content = 'snippets', content_rowid = 'id'
+);
+CREATE TRIGGER IF NOT EXISTS snippets_fts_insert AFTER INSERT ON snippets BEGIN
+    INSERT INTO snippets_fts (rowid, name, description, sql, tags)
+    VALUES (new.id, new.name, new.description, new.sql, new.tags);
+END;
+CREATE TRIGGER IF NOT EXISTS snippets_fts_delete AFTER DELETE ON snippets BEGIN
+    INSERT INTO snippets_fts (snippets_fts, rowid, name, description, sql, tags)
+    VALUES ('delete', old.id, old.name, old.description, old.sql, old.tags);
+END;
+CREATE TRIGGER IF NOT EXISTS snippets_fts_update AFTER UPDATE ON snippets BEGIN
+    INSERT INTO snippets_fts (snippets_fts, rowid, name, description, sql, tags)
+    VALUES ('delete', old.id, old.name, old.description, old.sql, old.tags);
+    INSERT INTO snippets_fts (rowid, name, description, sql, tags)
+    VALUES (new.id, new.name, new.description, new.sql, new.tags);
+END;
+";
+
+// Serphic 自己的 SQLite 檔案，保存查詢歷史與程式碼片段，第一次使用時在應用程式資料目錄建立
+#[derive(Default)]
+pub(crate) struct AppDatabase(tokio::sync::OnceCell<sqlx::SqlitePool>);
+
+impl AppDatabase {
+    pub(crate) async fn pool(&self, app: &AppHandle) -> Result<&sqlx::SqlitePool, String> {
+        self.0.get_or_try_init(|| open_app_database(app)).await
+    }
+}
+
+async fn open_app_database(app: &AppHandle) -> Result<sqlx::SqlitePool, String> {
+    let directory = app.path().app_data_dir().map_err(|e| format!("找不到應用程式資料目錄: {e}"))?;
+    std::fs::create_dir_all(&directory).map_err(|e| format!("建立應用程式資料目錄錯誤: {e}"))?;
+
+    let options = sqlx::sqlite::SqliteConnectOptions::new()
+        .filename(directory.join(APP_DATABASE_FILE))
+        .create_if_missing(true)
+        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal);
+    let pool = sqlx::SqlitePool::connect_with(options)
+        .await
+        .map_err(|e| format!("開啟應用程式資料庫錯誤: {e}"))?;
+    pool.execute(sqlx::raw_sql(APP_DATABASE_SCHEMA))
+        .await
+        .map_err(|e| format!("建立應用程式資料表錯誤: {e}"))?;
+    Ok(pool)
+}
+
+// Unix 時間（毫秒）
+pub(crate) fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+// LIKE 的搜尋樣式，搭配 ESCAPE '\' 使用
+pub(crate) fn like_pattern(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{escaped}%")
+}
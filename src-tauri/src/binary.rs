@@ -0,0 +1,421 @@
+use std::path::PathBuf;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+use crate::connection::DbPool;
+use crate::dialect::{quote_identifier, sql_literal};
+use crate::export::value_text;
+use crate::query::{stream_query, ResultColumn, RowHandler};
+use crate::spatial::GeometryFormat;
+use crate::sql_guard::ensure_writable;
+use crate::DatabaseConnection;
+
+// 查詢結果中二進位值保留的位元組數，3 的倍數讓 base64 可以直接截斷
+const BINARY_PREVIEW_BYTES: usize = 768;
+// 上傳到儲存格的檔案大小上限
+const MAX_UPLOAD_BYTES: u64 = 256 * 1024 * 1024;
+// 判斷是否為文字時檢查的位元組數
+const TEXT_SNIFF_BYTES: usize = 1024;
+
+// 依檔案開頭的特徵判斷的類型
+const MAGIC_NUMBERS: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"SQLite format 3\0", "application/vnd.sqlite3"),
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FetchCellRequest {
+    pub connection: DatabaseConnection,
+    pub table: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub column: String,
+    pub key: Map<String, Value>, // 識別資料列的欄位與值，通常為主鍵
+    #[serde(default)]
+    pub save: bool, // 儲存為檔案而不返回內容
+    #[serde(default)]
+    pub file_path: Option<String>, // 儲存時未指定則開啟儲存對話框
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FetchCellResult {
+    pub success: bool,
+    pub value: Option<Value>, // 完整的值，儲存為檔案時為 None
+    pub length: Option<u64>, // 二進位值的位元組數
+    pub mime_type: Option<String>,
+    pub file_path: Option<String>,
+    pub execution_time: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UploadCellRequest {
+    pub connection: DatabaseConnection,
+    pub table: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub column: String,
+    pub key: Map<String, Value>,
+    #[serde(default)]
+    pub file_path: Option<String>, // 未指定時開啟選擇檔案對話框
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UploadCellResult {
+    pub success: bool,
+    pub file_path: Option<String>,
+    pub length: u64,
+    pub mime_type: Option<String>,
+    pub execution_time: u64,
+    pub message: String,
+}
+
+// 二進位的單元格：$binary 為 base64，truncated 為 true 時只包含開頭的部分
+pub(crate) fn binary_value(bytes: &[u8]) -> Value {
+    json!({
+        "$binary": STANDARD.encode(bytes),
+        "length": bytes.len(),
+        "mime_type": sniff_mime_type(bytes),
+        "truncated": false,
+    })
+}
+
+// 查詢結果只返回預覽，完整內容透過 fetch_cell 取得
+pub(crate) fn truncate_binary(value: &mut Value) {
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    let preview_chars = BINARY_PREVIEW_BYTES / 3 * 4;
+    if let Some(Value::String(encoded)) = object.get_mut("$binary") {
+        if encoded.len() > preview_chars {
+            encoded.truncate(preview_chars);
+            object.insert("truncated".to_string(), Value::Bool(true));
+        }
+    }
+}
+
+// 完整的二進位值，預覽或其他值返回 None
+pub(crate) fn binary_bytes(value: &Value) -> Option<Vec<u8>> {
+    let object = value.as_object()?;
+    if object.get("truncated").and_then(Value::as_bool) != Some(false) {
+        return None;
+    }
+    let encoded = object.get("$binary")?.as_str()?;
+    STANDARD.decode(encoded).ok()
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub(crate) fn sniff_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    if let Some((_, mime_type)) = MAGIC_NUMBERS.iter().find(|(magic, _)| bytes.starts_with(magic)) {
+        return Some(mime_type);
+    }
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    if bytes.is_empty() {
+        return None;
+    }
+    if looks_like_text(bytes) {
+        return Some("text/plain");
+    }
+    if looks_like_protobuf(bytes) {
+        return Some("application/x-protobuf");
+    }
+    None
+}
+
+// 開頭是有效的 UTF-8 且沒有控制字元（結尾被截斷的多位元組字元不算錯誤）
+fn looks_like_text(bytes: &[u8]) -> bool {
+    let head = &bytes[..bytes.len().min(TEXT_SNIFF_BYTES)];
+    let text = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(error) if error.error_len().is_none() && bytes.len() > TEXT_SNIFF_BYTES => {
+            // 只在截斷處的字元不完整
+            match std::str::from_utf8(&head[..error.valid_up_to()]) {
+                Ok(text) => text,
+                Err(_) => return false,
+            }
+        }
+        Err(_) => return false,
+    };
+    text.chars().all(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+}
+
+// 整段資料都能解析為 protobuf 的欄位（varint 標籤加上合法的 wire type）
+fn looks_like_protobuf(bytes: &[u8]) -> bool {
+    fn varint(rest: &mut &[u8]) -> Option<u64> {
+        let mut value = 0u64;
+        for (i, byte) in rest.iter().take(10).enumerate() {
+            value |= u64::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                *rest = &rest[i + 1..];
+                return Some(value);
+            }
+        }
+        None
+    }
+    fn skip(rest: &mut &[u8], len: u64) -> Option<()> {
+        let len = usize::try_from(len).ok().filter(|&len| len <= rest.len())?;
+        *rest = &rest[len..];
+        Some(())
+    }
+
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        let Some(key) = varint(&mut rest) else {
+            return false;
+        };
+        if key >> 3 == 0 || key >> 3 > 0x1FFF_FFFF {
+            return false;
+        }
+        let valid = match key & 7 {
+            0 => varint(&mut rest).map(|_| ()),
+            1 => skip(&mut rest, 8),
+            2 => varint(&mut rest).and_then(|len| skip(&mut rest, len)),
+            5 => skip(&mut rest, 4),
+            _ => None,
+        };
+        if valid.is_none() {
+            return false;
+        }
+    }
+    true
+}
+
+// 以欄位與值組成 WHERE 條件，NULL 使用 IS NULL
+fn key_condition(db_type: &str, key: &Map<String, Value>) -> Result<String, String> {
+    if key.is_empty() {
+        return Err("請指定識別資料列的欄位".to_string());
+    }
+    let conditions: Vec<String> = key
+        .iter()
+        .map(|(column, value)| {
+            let column = quote_identifier(db_type, column);
+            match value {
+                Value::Null => format!("{column} IS NULL"),
+                value => format!("{column} = {}", sql_literal(db_type, value)),
+            }
+        })
+        .collect();
+    Ok(conditions.join(" AND "))
+}
+
+fn qualified_table(db_type: &str, schema: &Option<String>, table: &str) -> String {
+    let table = quote_identifier(db_type, table);
+    match schema {
+        Some(schema) if !schema.is_empty() => format!("{}.{table}", quote_identifier(db_type, schema)),
+        _ => table,
+    }
+}
+
+// 最多保留兩行，用來確認條件只對應到一行
+#[derive(Default)]
+struct CellRows(Vec<Value>);
+
+impl RowHandler for CellRows {
+    fn on_columns(&mut self, _columns: &[ResultColumn]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_row(&mut self, mut values: Vec<Value>) -> Result<(), String> {
+        self.0.push(values.pop().unwrap_or(Value::Null));
+        Ok(())
+    }
+
+    fn is_full(&self) -> bool {
+        self.0.len() > 1
+    }
+}
+
+#[tauri::command]
+pub async fn fetch_cell(app: AppHandle, request: FetchCellRequest) -> Result<FetchCellResult, String> {
+    let start_time = std::time::Instant::now();
+    let db_type = request.connection.db_type.as_str();
+    let sql = format!(
+        "SELECT {} FROM {} WHERE {}",
+        quote_identifier(db_type, &request.column),
+        qualified_table(db_type, &request.schema, &request.table),
+        key_condition(db_type, &request.key)?,
+    );
+
+    let mut rows = CellRows::default();
+    stream_query(&request.connection, &sql, GeometryFormat::GeoJson, &mut rows).await?;
+    let value = match rows.0.len() {
+        0 => return Err("找不到符合條件的資料列".to_string()),
+        1 => rows.0.remove(0),
+        _ => return Err("條件對應到多個資料列，請使用主鍵".to_string()),
+    };
+
+    let bytes = binary_bytes(&value);
+    let length = bytes.as_ref().map(|bytes| bytes.len() as u64);
+    let mime_type = bytes.as_deref().and_then(sniff_mime_type).map(str::to_string);
+
+    if !request.save {
+        return Ok(FetchCellResult {
+            success: true,
+            value: Some(value),
+            length,
+            mime_type,
+            file_path: None,
+            execution_time: start_time.elapsed().as_millis() as u64,
+            message: "已取得儲存格內容".to_string(),
+        });
+    }
+
+    let path = match &request.file_path {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => match choose_cell_path(&app, true).await? {
+            Some(path) => path,
+            None => {
+                return Ok(FetchCellResult {
+                    success: false,
+                    value: None,
+                    length,
+                    mime_type,
+                    file_path: None,
+                    execution_time: start_time.elapsed().as_millis() as u64,
+                    message: "已取消儲存".to_string(),
+                })
+            }
+        },
+    };
+    // 非二進位的值以文字儲存
+    let contents = bytes.unwrap_or_else(|| value_text(&value).into_bytes());
+    std::fs::write(&path, &contents).map_err(|e| format!("寫入檔案錯誤: {e}"))?;
+
+    let file_path = path.to_string_lossy().into_owned();
+    let written = contents.len();
+    Ok(FetchCellResult {
+        success: true,
+        value: None,
+        length,
+        mime_type,
+        file_path: Some(file_path.clone()),
+        execution_time: start_time.elapsed().as_millis() as u64,
+        message: format!("已儲存 {written} 位元組至 {file_path}"),
+    })
+}
+
+#[tauri::command]
+pub async fn upload_cell(app: AppHandle, request: UploadCellRequest) -> Result<UploadCellResult, String> {
+    let start_time = std::time::Instant::now();
+    ensure_writable(&request.connection, "上傳檔案到儲存格")?;
+    let db_type = request.connection.db_type.as_str();
+    let condition = key_condition(db_type, &request.key)?;
+
+    let path = match &request.file_path {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => match choose_cell_path(&app, false).await? {
+            Some(path) => path,
+            None => {
+                return Ok(UploadCellResult {
+                    success: false,
+                    file_path: None,
+                    length: 0,
+                    mime_type: None,
+                    execution_time: 0,
+                    message: "已取消上傳".to_string(),
+                })
+            }
+        },
+    };
+    let file_path = path.to_string_lossy().into_owned();
+
+    let size = std::fs::metadata(&path).map_err(|e| format!("無法讀取檔案: {e}"))?.len();
+    if size > MAX_UPLOAD_BYTES {
+        let limit = MAX_UPLOAD_BYTES / 1024 / 1024;
+        return Err(format!("檔案超過 {limit} MB 的上限"));
+    }
+    let bytes = std::fs::read(&path).map_err(|e| format!("無法讀取檔案: {e}"))?;
+
+    let placeholder = if db_type == "postgresql" { "$1" } else { "?" };
+    let sql = format!(
+        "UPDATE {} SET {} = {placeholder} WHERE {condition}",
+        qualified_table(db_type, &request.schema, &request.table),
+        quote_identifier(db_type, &request.column),
+    );
+
+    let pool = DbPool::connect(&request.connection).await?;
+    let result = update_cell(&pool, &sql, &bytes).await;
+    pool.close().await;
+    result?;
+
+    let length = bytes.len() as u64;
+    Ok(UploadCellResult {
+        success: true,
+        file_path: Some(file_path.clone()),
+        length,
+        mime_type: sniff_mime_type(&bytes).map(str::to_string),
+        execution_time: start_time.elapsed().as_millis() as u64,
+        message: format!("已上傳 {length} 位元組，來源 {file_path}"),
+    })
+}
+
+// 在交易中更新，條件沒有剛好對應到一行時回滾
+async fn update_cell(pool: &DbPool, sql: &str, bytes: &[u8]) -> Result<(), String> {
+    let affected_rows = match pool {
+        DbPool::Sqlite(pool) => {
+            let mut tx = pool.begin().await.map_err(|e| format!("開始交易錯誤: {e}"))?;
+            let result = sqlx::query(sql).bind(bytes).execute(&mut *tx).await.map_err(|e| format!("執行錯誤: {e}"))?;
+            finish_update(tx, result.rows_affected()).await?
+        }
+        DbPool::MySql(pool) => {
+            let mut tx = pool.begin().await.map_err(|e| format!("開始交易錯誤: {e}"))?;
+            let result = sqlx::query(sql).bind(bytes).execute(&mut *tx).await.map_err(|e| format!("執行錯誤: {e}"))?;
+            finish_update(tx, result.rows_affected()).await?
+        }
+        DbPool::Postgres(pool) => {
+            let mut tx = pool.begin().await.map_err(|e| format!("開始交易錯誤: {e}"))?;
+            let result = sqlx::query(sql).bind(bytes).execute(&mut *tx).await.map_err(|e| format!("執行錯誤: {e}"))?;
+            finish_update(tx, result.rows_affected()).await?
+        }
+    };
+    match affected_rows {
+        1 => Ok(()),
+        0 => Err("找不到符合條件的資料列".to_string()),
+        _ => Err("條件對應到多個資料列，請使用主鍵".to_string()),
+    }
+}
+
+async fn finish_update<DB: sqlx::Database>(tx: sqlx::Transaction<'_, DB>, affected_rows: u64) -> Result<u64, String> {
+    if affected_rows == 1 {
+        tx.commit().await.map_err(|e| format!("提交交易錯誤: {e}"))?;
+    } else {
+        tx.rollback().await.map_err(|e| format!("回滾交易錯誤: {e}"))?;
+    }
+    Ok(affected_rows)
+}
+
+async fn choose_cell_path(app: &AppHandle, save: bool) -> Result<Option<PathBuf>, String> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+
+    let dialog = app.dialog().file();
+    if save {
+        dialog.save_file(move |path| {
+            let _ = sender.send(path);
+        });
+    } else {
+        dialog.pick_file(move |path| {
+            let _ = sender.send(path);
+        });
+    }
+
+    match receiver.await.map_err(|e| format!("檔案對話框錯誤: {e}"))? {
+        Some(path) => path.into_path().map(Some).map_err(|e| format!("無效的檔案路徑: {e}")),
+        None => Ok(None),
+    }
+}
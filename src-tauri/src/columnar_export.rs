@@ -0,0 +1,288 @@
+use std::fs::File;
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, BooleanBuilder, Date32Builder, Float32Builder, Float64Builder, Int16Builder, Int32Builder,
+    Int64Builder, StringBuilder, TimestampMicrosecondBuilder, UInt16Builder, UInt32Builder, UInt64Builder,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde_json::Value;
+use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+use crate::export::{value_text, ExportWriter};
+use crate::query::ResultColumn;
+
+// 每批累積多少行後寫出一個 RecordBatch
+const BATCH_ROWS: usize = 8192;
+
+enum BatchSink {
+    Parquet(ArrowWriter<File>),
+    Ipc(FileWriter<File>),
+}
+
+// Parquet 與 Arrow IPC 匯出，依資料庫欄位型別建立 Arrow schema
+pub(crate) struct ColumnarWriter {
+    file: Option<File>,
+    parquet: bool,
+    db_type: String,
+    schema: Option<SchemaRef>,
+    sink: Option<BatchSink>,
+    buffer: Vec<Vec<Value>>,
+}
+
+impl ColumnarWriter {
+    pub(crate) fn parquet(file: File, db_type: &str) -> Self {
+        Self::new(file, db_type, true)
+    }
+
+    pub(crate) fn arrow_ipc(file: File, db_type: &str) -> Self {
+        Self::new(file, db_type, false)
+    }
+
+    fn new(file: File, db_type: &str, parquet: bool) -> Self {
+        Self {
+            file: Some(file),
+            parquet,
+            db_type: db_type.to_string(),
+            schema: None,
+            sink: None,
+            buffer: Vec::with_capacity(BATCH_ROWS),
+        }
+    }
+
+    fn flush_batch(&mut self) -> std::io::Result<()> {
+        let (Some(schema), Some(sink)) = (&self.schema, &mut self.sink) else {
+            return Ok(());
+        };
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let arrays: Vec<ArrayRef> = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| build_array(field.data_type(), &self.buffer, index))
+            .collect();
+        let batch = RecordBatch::try_new(schema.clone(), arrays).map_err(std::io::Error::other)?;
+
+        match sink {
+            BatchSink::Parquet(writer) => writer.write(&batch).map_err(std::io::Error::other)?,
+            BatchSink::Ipc(writer) => writer.write(&batch).map_err(std::io::Error::other)?,
+        }
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+// 將各資料庫的欄位型別名稱對應到 Arrow 型別
+pub(crate) fn arrow_type(db_type: &str, type_name: &str) -> DataType {
+    let timestamp_utc = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+    let timestamp = DataType::Timestamp(TimeUnit::Microsecond, None);
+
+    match (db_type, type_name) {
+        // SQLite 為動態型別，只對應明確的數值型別，其餘保留為文字
+        ("sqlite", "INTEGER") => DataType::Int64,
+        ("sqlite", "REAL") => DataType::Float64,
+        ("sqlite", "BOOLEAN") => DataType::Boolean,
+        ("sqlite", _) => DataType::Utf8,
+
+        (_, "BOOL" | "BOOLEAN") => DataType::Boolean,
+        (_, "INT2" | "SMALLINT" | "TINYINT" | "YEAR") => DataType::Int16,
+        (_, "INT4" | "INT" | "MEDIUMINT") => DataType::Int32,
+        (_, "INT8" | "BIGINT" | "OID") => DataType::Int64,
+        (_, "TINYINT UNSIGNED" | "SMALLINT UNSIGNED") => DataType::UInt16,
+        (_, "MEDIUMINT UNSIGNED" | "INT UNSIGNED") => DataType::UInt32,
+        (_, "BIGINT UNSIGNED") => DataType::UInt64,
+        (_, "FLOAT4" | "FLOAT") => DataType::Float32,
+        (_, "FLOAT8" | "DOUBLE") => DataType::Float64,
+        (_, "DATE") => DataType::Date32,
+        // MySQL 的 TIMESTAMP 以 UTC 讀取，DATETIME 與 PostgreSQL 的 TIMESTAMP 沒有時區
+        ("mysql", "TIMESTAMP") => timestamp_utc,
+        (_, "TIMESTAMPTZ") => timestamp_utc,
+        (_, "DATETIME" | "TIMESTAMP") => timestamp,
+        _ => DataType::Utf8,
+    }
+}
+
+fn value_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.trim().parse().ok(),
+        Value::Bool(b) => Some(*b as i64),
+        _ => None,
+    }
+}
+
+fn value_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.trim().parse().ok(),
+        Value::Bool(b) => Some(*b as u64),
+        _ => None,
+    }
+}
+
+fn value_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::Number(n) => n.as_f64().map(|n| n != 0.0),
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "true" | "t" | "1" | "yes" => Some(true),
+            "false" | "f" | "0" | "no" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn value_date32(value: &Value) -> Option<i32> {
+    let text = value.as_str()?;
+    let date = NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d").ok()?;
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?;
+    Some((date - epoch).num_days() as i32)
+}
+
+fn value_timestamp_micros(value: &Value) -> Option<i64> {
+    let text = value.as_str()?;
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return Some(datetime.timestamp_micros());
+    }
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|datetime| datetime.and_utc().timestamp_micros())
+}
+
+fn build_array(data_type: &DataType, rows: &[Vec<Value>], index: usize) -> ArrayRef {
+    let values = rows.iter().map(|row| row.get(index).unwrap_or(&Value::Null));
+
+    match data_type {
+        DataType::Boolean => {
+            let mut builder = BooleanBuilder::with_capacity(rows.len());
+            values.for_each(|value| builder.append_option(value_bool(value)));
+            Arc::new(builder.finish())
+        }
+        DataType::Int16 => {
+            let mut builder = Int16Builder::with_capacity(rows.len());
+            values.for_each(|value| builder.append_option(value_i64(value).and_then(|n| i16::try_from(n).ok())));
+            Arc::new(builder.finish())
+        }
+        DataType::Int32 => {
+            let mut builder = Int32Builder::with_capacity(rows.len());
+            values.for_each(|value| builder.append_option(value_i64(value).and_then(|n| i32::try_from(n).ok())));
+            Arc::new(builder.finish())
+        }
+        DataType::Int64 => {
+            let mut builder = Int64Builder::with_capacity(rows.len());
+            values.for_each(|value| builder.append_option(value_i64(value)));
+            Arc::new(builder.finish())
+        }
+        DataType::UInt16 => {
+            let mut builder = UInt16Builder::with_capacity(rows.len());
+            values.for_each(|value| builder.append_option(value_u64(value).and_then(|n| u16::try_from(n).ok())));
+            Arc::new(builder.finish())
+        }
+        DataType::UInt32 => {
+            let mut builder = UInt32Builder::with_capacity(rows.len());
+            values.for_each(|value| builder.append_option(value_u64(value).and_then(|n| u32::try_from(n).ok())));
+            Arc::new(builder.finish())
+        }
+        DataType::UInt64 => {
+            let mut builder = UInt64Builder::with_capacity(rows.len());
+            values.for_each(|value| builder.append_option(value_u64(value)));
+            Arc::new(builder.finish())
+        }
+        DataType::Float32 => {
+            let mut builder = Float32Builder::with_capacity(rows.len());
+            values.for_each(|value| builder.append_option(value_f64(value).map(|n| n as f32)));
+            Arc::new(builder.finish())
+        }
+        DataType::Float64 => {
+            let mut builder = Float64Builder::with_capacity(rows.len());
+            values.for_each(|value| builder.append_option(value_f64(value)));
+            Arc::new(builder.finish())
+        }
+        DataType::Date32 => {
+            let mut builder = Date32Builder::with_capacity(rows.len());
+            values.for_each(|value| builder.append_option(value_date32(value)));
+            Arc::new(builder.finish())
+        }
+        DataType::Timestamp(_, timezone) => {
+            let mut builder = TimestampMicrosecondBuilder::with_capacity(rows.len())
+                .with_timezone_opt(timezone.clone());
+            values.for_each(|value| builder.append_option(value_timestamp_micros(value)));
+            Arc::new(builder.finish())
+        }
+        _ => {
+            let mut builder = StringBuilder::with_capacity(rows.len(), rows.len() * 16);
+            values.for_each(|value| match value {
+                Value::Null => builder.append_null(),
+                other => builder.append_value(value_text(other)),
+            });
+            Arc::new(builder.finish())
+        }
+    }
+}
+
+impl ExportWriter for ColumnarWriter {
+    fn begin(&mut self, columns: &[ResultColumn]) -> std::io::Result<()> {
+        // 一個檔案只能有一個 schema
+        let Some(file) = self.file.take() else {
+            return Err(std::io::Error::other("Parquet/Arrow 匯出只支援單一結果集"));
+        };
+
+        let fields: Vec<Field> = columns
+            .iter()
+            .map(|column| Field::new(&column.name, arrow_type(&self.db_type, &column.type_name), true))
+            .collect();
+        let schema: SchemaRef = Arc::new(Schema::new(fields));
+
+        let sink = if self.parquet {
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build();
+            BatchSink::Parquet(ArrowWriter::try_new(file, schema.clone(), Some(properties)).map_err(std::io::Error::other)?)
+        } else {
+            BatchSink::Ipc(FileWriter::try_new(file, &schema).map_err(std::io::Error::other)?)
+        };
+
+        self.schema = Some(schema);
+        self.sink = Some(sink);
+        Ok(())
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> std::io::Result<()> {
+        self.buffer.push(values.to_vec());
+        if self.buffer.len() >= BATCH_ROWS {
+            self.flush_batch()?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        self.flush_batch()?;
+        match self.sink.take() {
+            Some(BatchSink::Parquet(writer)) => {
+                writer.close().map_err(std::io::Error::other)?;
+            }
+            Some(BatchSink::Ipc(mut writer)) => {
+                writer.finish().map_err(std::io::Error::other)?;
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Column, Executor, Row, TypeInfo};
+
+use crate::query::ResultColumn;
+
+// 介面用來決定對齊與編輯器的通用型別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogicalType {
+    Integer,
+    Decimal,
+    Float,
+    Boolean,
+    Text,
+    Binary,
+    Date,
+    Time,
+    Datetime,
+    Json,
+    Uuid,
+    Array,
+    Geometry,
+    Other,
+}
+
+// 結果欄位的來源資料表，只有直接選取資料表欄位時才有
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnSource {
+    pub schema: Option<String>,
+    pub table: String,
+    pub column: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnMetadata {
+    pub name: String,
+    pub type_name: String, // 資料庫回報的型別名稱
+    pub logical_type: LogicalType,
+    pub nullable: Option<bool>, // 無法判斷時為 None
+    pub source: Option<ColumnSource>,
+}
+
+pub(crate) fn logical_type(db_type: &str, type_name: &str) -> LogicalType {
+    let name = type_name.to_uppercase();
+    if name.ends_with("[]") {
+        return LogicalType::Array;
+    }
+    if matches!(name.as_str(), "GEOMETRY" | "GEOGRAPHY") {
+        return LogicalType::Geometry;
+    }
+
+    match db_type {
+        // SQLite 依據宣告型別的親和性規則判斷
+        "sqlite" => match name.as_str() {
+            "BOOLEAN" => LogicalType::Boolean,
+            "DATE" => LogicalType::Date,
+            "TIME" => LogicalType::Time,
+            "DATETIME" => LogicalType::Datetime,
+            _ if name.contains("INT") => LogicalType::Integer,
+            _ if name.contains("CHAR") || name.contains("CLOB") || name.contains("TEXT") => LogicalType::Text,
+            _ if name.contains("BLOB") => LogicalType::Binary,
+            _ if name.contains("REAL") || name.contains("FLOA") || name.contains("DOUB") => LogicalType::Float,
+            "NUMERIC" | "DECIMAL" => LogicalType::Decimal,
+            _ => LogicalType::Other,
+        },
+        "mysql" => match name.trim_end_matches(" UNSIGNED") {
+            "BOOLEAN" => LogicalType::Boolean,
+            "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" | "YEAR" => LogicalType::Integer,
+            "DECIMAL" => LogicalType::Decimal,
+            "FLOAT" | "DOUBLE" => LogicalType::Float,
+            "DATE" => LogicalType::Date,
+            "TIME" => LogicalType::Time,
+            "DATETIME" | "TIMESTAMP" => LogicalType::Datetime,
+            "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" | "SET" => LogicalType::Text,
+            "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "BIT" => LogicalType::Binary,
+            "JSON" => LogicalType::Json,
+            _ => LogicalType::Other,
+        },
+        "postgresql" => match name.as_str() {
+            "BOOL" => LogicalType::Boolean,
+            "INT2" | "INT4" | "INT8" | "OID" => LogicalType::Integer,
+            "NUMERIC" | "MONEY" => LogicalType::Decimal,
+            "FLOAT4" | "FLOAT8" => LogicalType::Float,
+            "DATE" => LogicalType::Date,
+            "TIME" | "TIMETZ" => LogicalType::Time,
+            "TIMESTAMP" | "TIMESTAMPTZ" => LogicalType::Datetime,
+            "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" | "CHAR" | "CITEXT" => LogicalType::Text,
+            "BYTEA" => LogicalType::Binary,
+            "JSON" | "JSONB" => LogicalType::Json,
+            "UUID" => LogicalType::Uuid,
+            _ => LogicalType::Other,
+        },
+        _ => LogicalType::Other,
+    }
+}
+
+// 無法取得描述時只使用讀取資料列時得到的名稱與型別
+pub(crate) fn streamed_metadata(db_type: &str, streamed: &[ResultColumn]) -> Vec<ColumnMetadata> {
+    streamed
+        .iter()
+        .map(|column| ColumnMetadata {
+            name: column.name.clone(),
+            type_name: column.type_name.clone(),
+            logical_type: logical_type(db_type, &column.type_name),
+            nullable: None,
+            source: None,
+        })
+        .collect()
+}
+
+// 以 describe 補上可為 NULL 的資訊，欄位數量不一致時表示描述的不是同一個結果集
+fn describe_metadata<DB: sqlx::Database>(
+    describe: sqlx::Describe<DB>,
+    db_type: &str,
+    streamed: &[ResultColumn],
+) -> Option<(Vec<ColumnMetadata>, sqlx::Describe<DB>)> {
+    if !streamed.is_empty() && describe.columns().len() != streamed.len() {
+        return None;
+    }
+
+    let metadata = describe
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            // 運算式欄位在描述中可能沒有型別，改用實際資料列的型別
+            let described = column.type_info();
+            let type_name = match streamed.get(i) {
+                Some(streamed) if described.is_null() || described.name() == "NULL" => streamed.type_name.clone(),
+                _ => described.name().to_string(),
+            };
+            ColumnMetadata {
+                name: column.name().to_string(),
+                logical_type: logical_type(db_type, &type_name),
+                type_name,
+                nullable: describe.nullable(i),
+                source: None,
+            }
+        })
+        .collect();
+    Some((metadata, describe))
+}
+
+// SQLite 的 sqlx 驅動不提供欄位的來源資料表
+pub(crate) async fn sqlite_columns(
+    conn: &mut sqlx::SqliteConnection,
+    sql: &str,
+    streamed: &[ResultColumn],
+) -> Vec<ColumnMetadata> {
+    let describe = conn.describe(sql).await.ok();
+    match describe.and_then(|describe| describe_metadata(describe, "sqlite", streamed)) {
+        Some((metadata, _)) => metadata,
+        None => streamed_metadata("sqlite", streamed),
+    }
+}
+
+// sqlx 0.8 的 MySQL 驅動沒有公開欄位定義中的 org_table，因此沒有來源資料表
+pub(crate) async fn mysql_columns(
+    conn: &mut sqlx::MySqlConnection,
+    sql: &str,
+    streamed: &[ResultColumn],
+) -> Vec<ColumnMetadata> {
+    let describe = conn.describe(sql).await.ok();
+    match describe.and_then(|describe| describe_metadata(describe, "mysql", streamed)) {
+        Some((metadata, _)) => metadata,
+        None => streamed_metadata("mysql", streamed),
+    }
+}
+
+// 以 relation_id 與 attnum 在 pg_attribute 查出來源資料表與欄位名稱
+pub(crate) async fn postgres_columns(
+    conn: &mut sqlx::PgConnection,
+    sql: &str,
+    streamed: &[ResultColumn],
+) -> Vec<ColumnMetadata> {
+    let describe = conn.describe(sql).await.ok();
+    let Some((mut metadata, describe)) = describe.and_then(|describe| describe_metadata(describe, "postgresql", streamed)) else {
+        return streamed_metadata("postgresql", streamed);
+    };
+
+    let origins: Vec<(usize, i64, i16)> = describe
+        .columns()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, column)| Some((i, i64::from(column.relation_id()?.0), column.relation_attribute_no()?)))
+        .collect();
+    if origins.is_empty() {
+        return metadata;
+    }
+
+    let relation_ids: Vec<i64> = origins.iter().map(|(_, relation_id, _)| *relation_id).collect();
+    let attribute_numbers: Vec<i16> = origins.iter().map(|(_, _, attnum)| *attnum).collect();
+    let rows = sqlx::query(
+        "SELECT a.attrelid::int8 AS relation_id, a.attnum, n.nspname, c.relname, a.attname
+         FROM unnest($1::int8[], $2::int2[]) AS origin (relation_id, attnum)
+         JOIN pg_attribute a ON a.attrelid = origin.relation_id::oid AND a.attnum = origin.attnum
+         JOIN pg_class c ON c.oid = a.attrelid
+         JOIN pg_namespace n ON n.oid = c.relnamespace",
+    )
+    .bind(relation_ids)
+    .bind(attribute_numbers)
+    .fetch_all(conn)
+    .await
+    .unwrap_or_default();
+
+    for row in rows {
+        let (Ok(relation_id), Ok(attnum)) = (row.try_get::<i64, _>("relation_id"), row.try_get::<i16, _>("attnum")) else {
+            continue;
+        };
+        let (Ok(schema), Ok(table), Ok(column)) = (
+            row.try_get::<String, _>("nspname"),
+            row.try_get::<String, _>("relname"),
+            row.try_get::<String, _>("attname"),
+        ) else {
+            continue;
+        };
+        for (i, _, _) in origins.iter().filter(|(_, id, number)| *id == relation_id && *number == attnum) {
+            metadata[*i].source = Some(ColumnSource {
+                schema: Some(schema.clone()),
+                table: table.clone(),
+                column: column.clone(),
+            });
+        }
+    }
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::Connection;
+
+    #[tokio::test]
+    async fn with_query_columns() {
+        let mut conn = sqlx::SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL, price REAL)").await.unwrap();
+
+        let sql = "WITH cheap AS (SELECT id, name, price FROM items WHERE price < 10) SELECT id, name, price FROM cheap";
+        assert!(crate::sql_guard::returns_rows(sql, "sqlite"));
+
+        // 沒有資料列時只能依賴 describe 的結果
+        let metadata = sqlite_columns(&mut conn, sql, &[]).await;
+        let columns: Vec<(&str, LogicalType)> = metadata.iter().map(|column| (column.name.as_str(), column.logical_type)).collect();
+        assert_eq!(
+            columns,
+            [("id", LogicalType::Integer), ("name", LogicalType::Text), ("price", LogicalType::Float)]
+        );
+    }
+}
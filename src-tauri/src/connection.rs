@@ -0,0 +1,405 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sqlx::Executor;
+
+use crate::dialect::{quote_identifier, quote_string};
+use crate::{DatabaseConnection, SqlCipherSettings, SqlitePragma};
+
+// SQLite 開啟檔案時返回 SQLITE_NOTADB 的錯誤碼
+const SQLITE_NOTADB: &str = "26";
+// SQLite 進度回呼之間執行的虛擬機指令數，用於檢查查詢是否逾時
+const SQLITE_PROGRESS_OPS: i32 = 1000;
+
+// 需要介面特別處理的失敗類型，錯誤訊息本身仍以字串傳遞
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    WrongKey, // 密鑰或 SQLCipher 相容性設定不正確
+    EncryptedDatabase, // 檔案已加密或不是 SQLite 資料庫，但未提供密鑰
+    CipherUnavailable, // 未以 sqlcipher 功能編譯，無法開啟加密資料庫
+    Timeout, // 查詢超過逾時設定，已在伺服器端或用戶端取消
+}
+
+impl ErrorKind {
+    fn message(self) -> &'static str {
+        match self {
+            Self::WrongKey => "密鑰錯誤，或 SQLCipher 相容性設定與檔案不符",
+            Self::EncryptedDatabase => "檔案已加密或不是 SQLite 資料庫，請提供密鑰",
+            Self::CipherUnavailable => "此版本未支援 SQLCipher，無法開啟加密資料庫",
+            Self::Timeout => "查詢逾時",
+        }
+    }
+
+}
+
+// 帶有失敗類型的錯誤；其他只需要訊息的呼叫者以 ? 轉為字串
+#[derive(Debug, Clone)]
+pub(crate) struct DbError {
+    pub kind: Option<ErrorKind>,
+    pub message: String,
+}
+
+impl From<ErrorKind> for DbError {
+    fn from(kind: ErrorKind) -> Self {
+        Self { kind: Some(kind), message: kind.message().to_string() }
+    }
+}
+
+impl From<String> for DbError {
+    fn from(message: String) -> Self {
+        Self { kind: None, message }
+    }
+}
+
+impl From<DbError> for String {
+    fn from(error: DbError) -> Self {
+        error.message
+    }
+}
+
+// SQLite 檔案路徑，未指定時使用內存資料庫
+pub(crate) fn sqlite_database_path(connection: &DatabaseConnection) -> String {
+    if connection.database.is_empty() {
+        ":memory:".to_string()
+    } else {
+        connection.database.clone()
+    }
+}
+
+pub(crate) fn mysql_url(connection: &DatabaseConnection) -> String {
+    format!(
+        "mysql://{}:{}@{}:{}/{}",
+        connection.username,
+        connection.password,
+        connection.host,
+        connection.port,
+        connection.database
+    )
+}
+
+pub(crate) fn postgres_url(connection: &DatabaseConnection) -> String {
+    // 雲端資料庫通常需要 SSL
+    let is_cloud_db = connection.host.contains("supabase.co") ||
+                      connection.host.contains("amazonaws.com") ||
+                      connection.host.contains("azure.com") ||
+                      connection.host.contains("googleusercontent.com");
+    let ssl_param = if is_cloud_db { "sslmode=require&" } else { "" };
+
+    format!(
+        "postgres://{}:{}@{}:{}/{}?{}connect_timeout=10",
+        connection.username,
+        connection.password,
+        connection.host,
+        connection.port,
+        connection.database,
+        ssl_param
+    )
+}
+
+// PRAGMA 名稱只能是識別字，值只能是簡單的字詞或單引號字串，避免插入其他語句
+fn valid_pragma(pragma: &SqlitePragma) -> bool {
+    let name_valid = !pragma.name.is_empty()
+        && pragma.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let value = pragma.value.trim();
+    let value_valid = if let Some(quoted) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        !quoted.contains('\'')
+    } else {
+        !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    };
+    name_valid && value_valid
+}
+
+// 伺服器端逾時取消查詢時的錯誤訊息
+pub(crate) fn is_timeout_error(db_type: &str, message: &str) -> bool {
+    let message = message.to_lowercase();
+    match db_type {
+        "postgresql" => message.contains("statement timeout"),
+        "mysql" => message.contains("maximum statement execution time exceeded") || message.contains("max_statement_time"),
+        "sqlite" => message.contains("interrupted"),
+        _ => false,
+    }
+}
+
+pub(crate) fn timeout_error(limit: Duration) -> DbError {
+    let millis = limit.as_millis();
+    DbError {
+        kind: Some(ErrorKind::Timeout),
+        message: format!("{}（超過 {millis} 毫秒）", ErrorKind::Timeout.message()),
+    }
+}
+
+fn encryption_key(connection: &DatabaseConnection) -> Option<&str> {
+    connection.encryption_key.as_deref().filter(|key| !key.is_empty())
+}
+
+// SQLCipher 的設定，sqlx 會在 key 之後、其他 PRAGMA 之前依序套用
+fn cipher_pragmas(settings: &SqlCipherSettings) -> Result<Vec<(&'static str, String)>, String> {
+    let mut pragmas = Vec::new();
+    if let Some(header_size) = settings.plaintext_header_size {
+        pragmas.push(("cipher_plaintext_header_size", header_size.to_string()));
+    }
+    if let Some(kdf_iter) = settings.kdf_iter {
+        pragmas.push(("kdf_iter", kdf_iter.to_string()));
+    }
+    if let Some(algorithm) = &settings.kdf_algorithm {
+        let algorithm = algorithm.to_uppercase();
+        if !matches!(algorithm.as_str(), "PBKDF2_HMAC_SHA1" | "PBKDF2_HMAC_SHA256" | "PBKDF2_HMAC_SHA512") {
+            return Err(format!("不支援的 KDF 演算法: {algorithm}"));
+        }
+        pragmas.push(("cipher_kdf_algorithm", algorithm));
+    }
+    if let Some(use_hmac) = settings.use_hmac {
+        pragmas.push(("cipher_use_hmac", if use_hmac { "ON" } else { "OFF" }.to_string()));
+    }
+    if let Some(compatibility) = settings.compatibility {
+        if !(1..=4).contains(&compatibility) {
+            return Err(format!("無效的 SQLCipher 相容版本: {compatibility}"));
+        }
+        pragmas.push(("cipher_compatibility", compatibility.to_string()));
+    }
+    if let Some(page_size) = settings.page_size {
+        if !(512..=65536).contains(&page_size) || !page_size.is_power_of_two() {
+            return Err(format!("無效的 SQLCipher 頁面大小: {page_size}"));
+        }
+        pragmas.push(("cipher_page_size", page_size.to_string()));
+    }
+    if let Some(algorithm) = &settings.hmac_algorithm {
+        let algorithm = algorithm.to_uppercase();
+        if !matches!(algorithm.as_str(), "HMAC_SHA1" | "HMAC_SHA256" | "HMAC_SHA512") {
+            return Err(format!("不支援的 HMAC 演算法: {algorithm}"));
+        }
+        pragmas.push(("cipher_hmac_algorithm", algorithm));
+    }
+    Ok(pragmas)
+}
+
+fn sqlite_connect_options(connection: &DatabaseConnection) -> Result<sqlx::sqlite::SqliteConnectOptions, DbError> {
+    let mut options = sqlx::sqlite::SqliteConnectOptions::new()
+        .filename(sqlite_database_path(connection))
+        .create_if_missing(false)
+        .read_only(connection.is_read_only())
+        .immutable(connection.db_type == "sqlite" && connection.immutable);
+
+    // PRAGMA key 必須在讀取資料庫之前執行，sqlx 固定把它排在所有 PRAGMA 的最前面
+    if let Some(key) = encryption_key(connection) {
+        if !cfg!(feature = "sqlcipher") {
+            return Err(ErrorKind::CipherUnavailable.into());
+        }
+        options = options.pragma("key", quote_string("sqlite", key));
+        for (name, value) in cipher_pragmas(&connection.cipher)? {
+            options = options.pragma(name, value);
+        }
+    }
+
+    for pragma in &connection.pragmas {
+        if !valid_pragma(pragma) {
+            let (name, value) = (&pragma.name, &pragma.value);
+            return Err(format!("無效的 PRAGMA 設定: {name} = {value}").into());
+        }
+        options = options.pragma(pragma.name.to_lowercase(), pragma.value.trim().to_string());
+    }
+
+    if !connection.extensions.is_empty() && !connection.allow_extensions {
+        return Err("此連接未允許載入擴充功能".to_string().into());
+    }
+    for extension in &connection.extensions {
+        options = match &extension.entry_point {
+            Some(entry_point) if !entry_point.is_empty() => {
+                options.extension_with_entrypoint(extension.path.clone(), entry_point.clone())
+            }
+            _ => options.extension(extension.path.clone()),
+        };
+    }
+
+    Ok(options)
+}
+
+pub(crate) async fn connect_sqlite(connection: &DatabaseConnection) -> Result<sqlx::SqlitePool, DbError> {
+    let mut attach_statements = Vec::with_capacity(connection.attached_databases.len());
+    for attached in &connection.attached_databases {
+        let alias = attached.alias.trim();
+        if alias.is_empty() || alias.eq_ignore_ascii_case("main") || alias.eq_ignore_ascii_case("temp") {
+            return Err(format!("無效的附加資料庫別名: {alias}").into());
+        }
+        // ATTACH 會自動建立不存在的檔案，先確認檔案存在
+        if attached.path != ":memory:" && !std::path::Path::new(&attached.path).exists() {
+            let path = &attached.path;
+            return Err(format!("找不到附加的資料庫: {path}").into());
+        }
+        attach_statements.push(format!(
+            "ATTACH DATABASE {} AS {}",
+            quote_string("sqlite", &attached.path),
+            quote_identifier("sqlite", alias)
+        ));
+    }
+
+    let statement_timeout = connection.statement_timeout;
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        // 連接池中每個新連接都需要重新附加，跨查詢才能持續使用
+        .after_connect(move |conn, _meta| {
+            let attach_statements = attach_statements.clone();
+            Box::pin(async move {
+                for sql in &attach_statements {
+                    conn.execute(sqlx::raw_sql(sql)).await?;
+                }
+                // SQLite 沒有伺服器端逾時，以進度回呼在期限後中斷執行
+                if let Some(limit) = statement_timeout {
+                    let deadline = Instant::now() + limit;
+                    conn.lock_handle().await?.set_progress_handler(SQLITE_PROGRESS_OPS, move || Instant::now() < deadline);
+                }
+                Ok(())
+            })
+        })
+        .connect_with(sqlite_connect_options(connection)?)
+        .await
+        .map_err(|e| sqlite_open_error(connection, e))?;
+
+    // 密鑰錯誤要到第一次讀取時才會發現，先讀取一次 schema 確認
+    if encryption_key(connection).is_some() {
+        if let Err(error) = pool.execute("SELECT count(*) FROM sqlite_master").await {
+            pool.close().await;
+            return Err(sqlite_open_error(connection, error));
+        }
+    }
+    Ok(pool)
+}
+
+fn sqlite_open_error(connection: &DatabaseConnection, error: sqlx::Error) -> DbError {
+    let not_a_database = error
+        .as_database_error()
+        .and_then(|database_error| database_error.code())
+        .is_some_and(|code| code == SQLITE_NOTADB);
+    match (not_a_database, encryption_key(connection).is_some()) {
+        (true, true) => ErrorKind::WrongKey.into(),
+        (true, false) => ErrorKind::EncryptedDatabase.into(),
+        _ => format!("SQLite 連接錯誤: {error}").into(),
+    }
+}
+
+// 唯讀連接在伺服器端也把工作階段設為唯讀，語句檢查之外的第二道防線
+pub(crate) async fn connect_mysql(connection: &DatabaseConnection) -> Result<sqlx::MySqlPool, String> {
+    let read_only = connection.is_read_only();
+    let statement_timeout = connection.statement_timeout;
+    sqlx::mysql::MySqlPoolOptions::new()
+        .after_connect(move |conn, _meta| {
+            Box::pin(async move {
+                if read_only {
+                    conn.execute(sqlx::raw_sql("SET SESSION TRANSACTION READ ONLY")).await?;
+                }
+                if let Some(limit) = statement_timeout {
+                    // MySQL 的 max_execution_time 只限制 SELECT；MariaDB 改用以秒為單位的 max_statement_time
+                    let millis = limit.as_millis();
+                    let mysql = format!("SET SESSION max_execution_time = {millis}");
+                    if conn.execute(sqlx::raw_sql(&mysql)).await.is_err() {
+                        let seconds = limit.as_secs_f64();
+                        let mariadb = format!("SET SESSION max_statement_time = {seconds}");
+                        // 兩者都不支援時只依賴用戶端逾時
+                        let _ = conn.execute(sqlx::raw_sql(&mariadb)).await;
+                    }
+                }
+                Ok(())
+            })
+        })
+        .connect(&mysql_url(connection))
+        .await
+        .map_err(|e| format!("MySQL 連接錯誤: {e}"))
+}
+
+pub(crate) async fn connect_postgres(connection: &DatabaseConnection) -> Result<sqlx::PgPool, String> {
+    let read_only = connection.is_read_only();
+    let statement_timeout = connection.statement_timeout;
+    sqlx::postgres::PgPoolOptions::new()
+        .after_connect(move |conn, _meta| {
+            Box::pin(async move {
+                if read_only {
+                    conn.execute(sqlx::raw_sql("SET default_transaction_read_only = on")).await?;
+                }
+                if let Some(limit) = statement_timeout {
+                    let millis = limit.as_millis();
+                    conn.execute(sqlx::raw_sql(&format!("SET statement_timeout = {millis}"))).await?;
+                }
+                Ok(())
+            })
+        })
+        .connect(&postgres_url(connection))
+        .await
+        .map_err(|e| format!("PostgreSQL 連接錯誤: {e}"))
+}
+
+// 依資料庫類型建立的連接池，供需要在多種資料庫執行原始 SQL 的功能共用
+pub(crate) enum DbPool {
+    Sqlite(sqlx::SqlitePool),
+    MySql(sqlx::MySqlPool),
+    Postgres(sqlx::PgPool),
+}
+
+impl DbPool {
+    pub(crate) async fn connect(connection: &DatabaseConnection) -> Result<Self, String> {
+        match connection.db_type.as_str() {
+            "sqlite" => Ok(Self::Sqlite(connect_sqlite(connection).await?)),
+            "mysql" => Ok(Self::MySql(connect_mysql(connection).await?)),
+            "postgresql" => Ok(Self::Postgres(connect_postgres(connection).await?)),
+            _ => Err("不支援的資料庫類型".to_string()),
+        }
+    }
+
+    pub(crate) async fn close(&self) {
+        match self {
+            Self::Sqlite(pool) => pool.close().await,
+            Self::MySql(pool) => pool.close().await,
+            Self::Postgres(pool) => pool.close().await,
+        }
+    }
+
+    // 依序綁定參數，查詢沒有結果或返回 NULL 時為 None
+    pub(crate) async fn fetch_count(&self, sql: &str, params: &[Option<&str>]) -> Result<Option<i64>, String> {
+        let result = match self {
+            Self::Sqlite(pool) => {
+                let query = params.iter().fold(sqlx::query_scalar::<sqlx::Sqlite, Option<i64>>(sql), |query, param| query.bind(*param));
+                query.fetch_optional(pool).await
+            }
+            Self::MySql(pool) => {
+                let query = params.iter().fold(sqlx::query_scalar::<sqlx::MySql, Option<i64>>(sql), |query, param| query.bind(*param));
+                query.fetch_optional(pool).await
+            }
+            Self::Postgres(pool) => {
+                let query = params.iter().fold(sqlx::query_scalar::<sqlx::Postgres, Option<i64>>(sql), |query, param| query.bind(*param));
+                query.fetch_optional(pool).await
+            }
+        };
+        result.map(Option::flatten).map_err(|e| format!("查詢錯誤: {e}"))
+    }
+
+    // 在同一個交易中依序執行多條語句，任一失敗即回滾，返回影響的總行數
+    pub(crate) async fn execute_transaction(&self, statements: &[String]) -> Result<u64, String> {
+        let mut affected_rows = 0;
+        match self {
+            Self::Sqlite(pool) => {
+                let mut tx = pool.begin().await.map_err(|e| format!("開始交易錯誤: {e}"))?;
+                for sql in statements {
+                    let result = (&mut *tx).execute(sqlx::raw_sql(sql)).await.map_err(|e| format!("執行錯誤: {e}"))?;
+                    affected_rows += result.rows_affected();
+                }
+                tx.commit().await.map_err(|e| format!("提交交易錯誤: {e}"))?;
+            }
+            Self::MySql(pool) => {
+                let mut tx = pool.begin().await.map_err(|e| format!("開始交易錯誤: {e}"))?;
+                for sql in statements {
+                    let result = (&mut *tx).execute(sqlx::raw_sql(sql)).await.map_err(|e| format!("執行錯誤: {e}"))?;
+                    affected_rows += result.rows_affected();
+                }
+                tx.commit().await.map_err(|e| format!("提交交易錯誤: {e}"))?;
+            }
+            Self::Postgres(pool) => {
+                let mut tx = pool.begin().await.map_err(|e| format!("開始交易錯誤: {e}"))?;
+                for sql in statements {
+                    let result = (&mut *tx).execute(sqlx::raw_sql(sql)).await.map_err(|e| format!("執行錯誤: {e}"))?;
+                    affected_rows += result.rows_affected();
+                }
+                tx.commit().await.map_err(|e| format!("提交交易錯誤: {e}"))?;
+            }
+        }
+        Ok(affected_rows)
+    }
+}
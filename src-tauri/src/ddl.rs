@@ -0,0 +1,463 @@
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::types::Oid;
+use sqlx::Row;
+
+use crate::connection::{connect_mysql, connect_postgres, connect_sqlite};
+use crate::dialect::quote_identifier;
+use crate::DatabaseConnection;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectDdlRequest {
+    pub connection: DatabaseConnection,
+    pub object_name: String,
+    pub object_type: String, // 'table', 'view', 'index', 'trigger', 'function', 'procedure'
+    pub schema: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectDdlResult {
+    pub success: bool,
+    pub ddl: String,
+    pub message: String,
+}
+
+#[tauri::command]
+pub async fn get_object_ddl(request: ObjectDdlRequest) -> Result<ObjectDdlResult, String> {
+    let result = match request.connection.db_type.as_str() {
+        "sqlite" => sqlite_object_ddl(&request).await,
+        "mysql" => mysql_object_ddl(&request).await,
+        "postgresql" => postgres_object_ddl(&request).await,
+        _ => return Err("不支援的資料庫類型".to_string()),
+    };
+
+    match result {
+        Ok(ddl) => Ok(ObjectDdlResult {
+            success: true,
+            ddl,
+            message: "取得物件定義成功".to_string(),
+        }),
+        Err(error) => Ok(ObjectDdlResult {
+            success: false,
+            ddl: String::new(),
+            message: format!("取得物件定義失敗: {error}"),
+        }),
+    }
+}
+
+pub(crate) async fn sqlite_object_ddl(request: &ObjectDdlRequest) -> Result<String, String> {
+    let pool = connect_sqlite(&request.connection).await?;
+
+    // sqlite_master 的 sql 欄位就是原始的 CREATE 語句，附加的資料庫各有自己的 sqlite_master
+    let master_table = match request.schema.as_deref() {
+        Some("temp") => "sqlite_temp_master".to_string(),
+        Some(schema) if !schema.is_empty() => format!("{}.sqlite_master", quote_identifier("sqlite", schema)),
+        _ => "sqlite_master".to_string(),
+    };
+    let row = sqlx::query(&format!("SELECT sql FROM {master_table} WHERE name = ? AND type = ?"))
+        .bind(&request.object_name)
+        .bind(&request.object_type)
+        .fetch_optional(&pool)
+        .await;
+    pool.close().await;
+    let row = row.map_err(|e| format!("查詢 sqlite_master 錯誤: {e}"))?;
+
+    let object_name = &request.object_name;
+    let row = row.ok_or_else(|| format!("找不到物件: {object_name}"))?;
+    let sql: Option<String> = row.try_get("sql")
+        .map_err(|e| format!("取得定義錯誤: {e}"))?;
+
+    // 自動建立的索引（例如 UNIQUE 約束）沒有儲存 SQL
+    match sql {
+        Some(sql) => Ok(format!("{sql};")),
+        None => Err(format!("{object_name} 為自動建立的物件，沒有儲存的定義")),
+    }
+}
+
+// SHOW CREATE 的結果在部分版本會以二進位字串返回
+pub(crate) fn mysql_text_column(row: &sqlx::mysql::MySqlRow, index: usize) -> Result<String, String> {
+    match row.try_get::<String, _>(index) {
+        Ok(text) => Ok(text),
+        Err(_) => row.try_get::<Vec<u8>, _>(index)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .map_err(|e| format!("取得定義錯誤: {e}")),
+    }
+}
+
+pub(crate) async fn mysql_object_ddl(request: &ObjectDdlRequest) -> Result<String, String> {
+    let pool = connect_mysql(&request.connection).await?;
+
+    let qualified_name = match &request.schema {
+        Some(schema) if !schema.is_empty() => format!(
+            "{}.{}",
+            quote_identifier("mysql", schema),
+            quote_identifier("mysql", &request.object_name)
+        ),
+        _ => quote_identifier("mysql", &request.object_name),
+    };
+
+    // SHOW CREATE 各類型返回的定義欄位位置不同
+    let (show_sql, column_index) = match request.object_type.as_str() {
+        "table" => (format!("SHOW CREATE TABLE {qualified_name}"), 1),
+        "view" => (format!("SHOW CREATE VIEW {qualified_name}"), 1),
+        "trigger" => (format!("SHOW CREATE TRIGGER {qualified_name}"), 2),
+        "procedure" => (format!("SHOW CREATE PROCEDURE {qualified_name}"), 2),
+        "function" => (format!("SHOW CREATE FUNCTION {qualified_name}"), 2),
+        "index" => {
+            let ddl = mysql_index_ddl(&pool, request).await;
+            pool.close().await;
+            return ddl;
+        }
+        other => {
+            pool.close().await;
+            return Err(format!("MySQL 不支援的物件類型: {other}"));
+        }
+    };
+
+    let row = sqlx::raw_sql(&show_sql).fetch_one(&pool).await;
+    pool.close().await;
+    let row = row.map_err(|e| format!("查詢執行錯誤: {e}"))?;
+
+    let ddl = mysql_text_column(&row, column_index)?;
+    Ok(format!("{ddl};"))
+}
+
+// MySQL 沒有 SHOW CREATE INDEX，由 information_schema.STATISTICS 重建
+async fn mysql_index_ddl(pool: &sqlx::MySqlPool, request: &ObjectDdlRequest) -> Result<String, String> {
+    let rows = sqlx::query(
+        "SELECT TABLE_NAME, COLUMN_NAME, SUB_PART, NON_UNIQUE, INDEX_TYPE
+         FROM information_schema.STATISTICS
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND INDEX_NAME = ?
+         ORDER BY TABLE_NAME, SEQ_IN_INDEX"
+    )
+        .bind(request.schema.as_deref().filter(|schema| !schema.is_empty()))
+        .bind(&request.object_name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查詢索引資訊錯誤: {e}"))?;
+
+    let object_name = &request.object_name;
+    let first = rows.first().ok_or_else(|| format!("找不到索引: {object_name}"))?;
+
+    let table_name = mysql_text_column(first, 0)?;
+    let non_unique: i64 = first.try_get(3).unwrap_or(1);
+    let index_type = mysql_text_column(first, 4).unwrap_or_default();
+
+    let mut columns = Vec::with_capacity(rows.len());
+    for row in &rows {
+        // 同名索引可能存在於不同表格，只取第一個表格
+        if mysql_text_column(row, 0)? != table_name {
+            continue;
+        }
+        let column_name = mysql_text_column(row, 1)?;
+        let sub_part: Option<i64> = row.try_get(2).unwrap_or(None);
+        match sub_part {
+            Some(length) => columns.push(format!("{}({length})", quote_identifier("mysql", &column_name))),
+            None => columns.push(quote_identifier("mysql", &column_name)),
+        }
+    }
+
+    if object_name == "PRIMARY" {
+        return Ok(format!(
+            "ALTER TABLE {} ADD PRIMARY KEY ({});",
+            quote_identifier("mysql", &table_name),
+            columns.join(", ")
+        ));
+    }
+
+    let kind = match (non_unique, index_type.as_str()) {
+        (_, "FULLTEXT") => "FULLTEXT ",
+        (_, "SPATIAL") => "SPATIAL ",
+        (0, _) => "UNIQUE ",
+        _ => "",
+    };
+
+    Ok(format!(
+        "CREATE {kind}INDEX {} ON {} ({});",
+        quote_identifier("mysql", object_name),
+        quote_identifier("mysql", &table_name),
+        columns.join(", ")
+    ))
+}
+
+pub(crate) async fn postgres_object_ddl(request: &ObjectDdlRequest) -> Result<String, String> {
+    let pool = connect_postgres(&request.connection).await?;
+    let schema = request.schema.as_deref()
+        .filter(|schema| !schema.is_empty())
+        .unwrap_or("public");
+
+    let ddl = match request.object_type.as_str() {
+        "table" => postgres_table_ddl(&pool, schema, &request.object_name, true).await,
+        "view" => postgres_view_ddl(&pool, schema, &request.object_name).await,
+        "index" => postgres_index_ddl(&pool, schema, &request.object_name).await,
+        "trigger" => postgres_trigger_ddl(&pool, schema, &request.object_name).await,
+        "function" | "procedure" => postgres_function_ddl(&pool, schema, &request.object_name).await,
+        other => Err(format!("PostgreSQL 不支援的物件類型: {other}")),
+    };
+
+    pool.close().await;
+    ddl
+}
+
+// 找出關聯物件的 OID、類型與帶引號的完整名稱
+async fn postgres_relation(
+    pool: &sqlx::PgPool,
+    schema: &str,
+    name: &str,
+) -> Result<(Oid, String, String), String> {
+    let row = sqlx::query(
+        "SELECT c.oid, c.relkind::text AS relkind, format('%I.%I', n.nspname, c.relname) AS qualified_name
+         FROM pg_catalog.pg_class c
+         JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+         WHERE n.nspname = $1 AND c.relname = $2"
+    )
+        .bind(schema)
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("查詢 pg_class 錯誤: {e}"))?
+        .ok_or_else(|| format!("找不到物件: {schema}.{name}"))?;
+
+    let oid: Oid = row.try_get("oid").map_err(|e| format!("取得 OID 錯誤: {e}"))?;
+    let relkind: String = row.try_get("relkind").map_err(|e| format!("取得物件類型錯誤: {e}"))?;
+    let qualified_name: String = row.try_get("qualified_name")
+        .map_err(|e| format!("取得物件名稱錯誤: {e}"))?;
+
+    Ok((oid, relkind, qualified_name))
+}
+
+fn quote_postgres_literal(text: &str) -> String {
+    let escaped = text.replace('\'', "''");
+    format!("'{escaped}'")
+}
+
+// foreign_keys 為 false 時不包含外鍵約束，由 postgres_foreign_key_ddl 另外建立
+pub(crate) async fn postgres_table_ddl(
+    pool: &sqlx::PgPool,
+    schema: &str,
+    name: &str,
+    foreign_keys: bool,
+) -> Result<String, String> {
+    let (oid, relkind, qualified_name) = postgres_relation(pool, schema, name).await?;
+    if relkind != "r" && relkind != "p" {
+        return Err(format!("{qualified_name} 不是表格"));
+    }
+
+    // 欄位定義：型別、預設值、自動編號與產生欄位
+    let column_rows = sqlx::query(
+        "SELECT quote_ident(a.attname) AS column_name,
+                pg_catalog.format_type(a.atttypid, a.atttypmod) AS data_type,
+                a.attnotnull AS not_null,
+                a.attidentity::text AS identity,
+                a.attgenerated::text AS generated,
+                pg_catalog.pg_get_expr(d.adbin, d.adrelid) AS default_expr,
+                pg_catalog.col_description(a.attrelid, a.attnum) AS comment
+         FROM pg_catalog.pg_attribute a
+         LEFT JOIN pg_catalog.pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+         WHERE a.attrelid = $1 AND a.attnum > 0 AND NOT a.attisdropped
+         ORDER BY a.attnum"
+    )
+        .bind(oid)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查詢欄位資訊錯誤: {e}"))?;
+
+    let mut definitions = Vec::new();
+    let mut column_comments = Vec::new();
+
+    for row in &column_rows {
+        let column_name: String = row.try_get("column_name").map_err(|e| format!("取得欄位名稱錯誤: {e}"))?;
+        let data_type: String = row.try_get("data_type").map_err(|e| format!("取得欄位型別錯誤: {e}"))?;
+        let not_null: bool = row.try_get("not_null").unwrap_or(false);
+        let identity: String = row.try_get("identity").unwrap_or_default();
+        let generated: String = row.try_get("generated").unwrap_or_default();
+        let default_expr: Option<String> = row.try_get("default_expr").unwrap_or(None);
+        let comment: Option<String> = row.try_get("comment").unwrap_or(None);
+
+        let mut definition = format!("    {column_name} {data_type}");
+        match (identity.as_str(), generated.as_str(), default_expr) {
+            ("a", _, _) => definition.push_str(" GENERATED ALWAYS AS IDENTITY"),
+            ("d", _, _) => definition.push_str(" GENERATED BY DEFAULT AS IDENTITY"),
+            (_, "s", Some(expr)) => definition.push_str(&format!(" GENERATED ALWAYS AS ({expr}) STORED")),
+            (_, _, Some(expr)) => definition.push_str(&format!(" DEFAULT {expr}")),
+            _ => {}
+        }
+        if not_null {
+            definition.push_str(" NOT NULL");
+        }
+        definitions.push(definition);
+
+        if let Some(comment) = comment {
+            column_comments.push(format!(
+                "COMMENT ON COLUMN {qualified_name}.{column_name} IS {};",
+                quote_postgres_literal(&comment)
+            ));
+        }
+    }
+
+    // 表格約束：主鍵、唯一、外鍵、檢查與排除約束
+    let constraint_rows = sqlx::query(
+        "SELECT quote_ident(conname) AS constraint_name,
+                pg_catalog.pg_get_constraintdef(oid, true) AS definition
+         FROM pg_catalog.pg_constraint
+         WHERE conrelid = $1 AND ($2 OR contype <> 'f')
+         ORDER BY CASE contype WHEN 'p' THEN 0 WHEN 'u' THEN 1 WHEN 'f' THEN 3 ELSE 2 END, conname"
+    )
+        .bind(oid)
+        .bind(foreign_keys)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查詢約束資訊錯誤: {e}"))?;
+
+    for row in &constraint_rows {
+        let constraint_name: String = row.try_get("constraint_name").map_err(|e| format!("取得約束名稱錯誤: {e}"))?;
+        let definition: String = row.try_get("definition").map_err(|e| format!("取得約束定義錯誤: {e}"))?;
+        definitions.push(format!("    CONSTRAINT {constraint_name} {definition}"));
+    }
+
+    let mut ddl = format!("CREATE TABLE {qualified_name} (\n{}\n)", definitions.join(",\n"));
+    if relkind == "p" {
+        let partition_key: Option<String> = sqlx::query_scalar("SELECT pg_catalog.pg_get_partkeydef($1)")
+            .bind(oid)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("查詢分區鍵錯誤: {e}"))?;
+        if let Some(partition_key) = partition_key {
+            ddl.push_str(&format!(" PARTITION BY {partition_key}"));
+        }
+    }
+    ddl.push_str(";\n");
+
+    // 不屬於約束的索引需要另外建立
+    let index_definitions: Vec<String> = sqlx::query_scalar(
+        "SELECT pg_catalog.pg_get_indexdef(i.indexrelid)
+         FROM pg_catalog.pg_index i
+         WHERE i.indrelid = $1
+           AND NOT EXISTS (SELECT 1 FROM pg_catalog.pg_constraint c WHERE c.conindid = i.indexrelid AND c.conrelid = i.indrelid)
+         ORDER BY i.indexrelid"
+    )
+        .bind(oid)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查詢索引資訊錯誤: {e}"))?;
+
+    for index_definition in index_definitions {
+        ddl.push_str(&format!("\n{index_definition};"));
+    }
+
+    let table_comment: Option<String> = sqlx::query_scalar("SELECT pg_catalog.obj_description($1, 'pg_class')")
+        .bind(oid)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("查詢註解錯誤: {e}"))?;
+
+    if let Some(comment) = table_comment {
+        ddl.push_str(&format!("\nCOMMENT ON TABLE {qualified_name} IS {};", quote_postgres_literal(&comment)));
+    }
+    for comment in column_comments {
+        ddl.push_str(&format!("\n{comment}"));
+    }
+
+    Ok(ddl.trim_end().to_string())
+}
+
+// 表格的外鍵約束，以 ALTER TABLE 語句返回
+pub(crate) async fn postgres_foreign_key_ddl(pool: &sqlx::PgPool, schema: &str, name: &str) -> Result<Vec<String>, String> {
+    let (oid, _, qualified_name) = postgres_relation(pool, schema, name).await?;
+
+    let rows = sqlx::query(
+        "SELECT quote_ident(conname) AS constraint_name,
+                pg_catalog.pg_get_constraintdef(oid, true) AS definition
+         FROM pg_catalog.pg_constraint
+         WHERE conrelid = $1 AND contype = 'f'
+         ORDER BY conname"
+    )
+        .bind(oid)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查詢外鍵資訊錯誤: {e}"))?;
+
+    let mut statements = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let constraint_name: String = row.try_get("constraint_name").map_err(|e| format!("取得約束名稱錯誤: {e}"))?;
+        let definition: String = row.try_get("definition").map_err(|e| format!("取得約束定義錯誤: {e}"))?;
+        statements.push(format!("ALTER TABLE {qualified_name} ADD CONSTRAINT {constraint_name} {definition};"));
+    }
+    Ok(statements)
+}
+
+pub(crate) async fn postgres_view_ddl(pool: &sqlx::PgPool, schema: &str, name: &str) -> Result<String, String> {
+    let (oid, relkind, qualified_name) = postgres_relation(pool, schema, name).await?;
+
+    let create = match relkind.as_str() {
+        "v" => "CREATE OR REPLACE VIEW",
+        "m" => "CREATE MATERIALIZED VIEW",
+        _ => return Err(format!("{qualified_name} 不是檢視表")),
+    };
+
+    let definition: String = sqlx::query_scalar("SELECT pg_catalog.pg_get_viewdef($1, true)")
+        .bind(oid)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("查詢檢視表定義錯誤: {e}"))?;
+
+    // pg_get_viewdef 的結果已包含結尾分號
+    Ok(format!("{create} {qualified_name} AS\n{}", definition.trim_end()))
+}
+
+async fn postgres_index_ddl(pool: &sqlx::PgPool, schema: &str, name: &str) -> Result<String, String> {
+    let (oid, relkind, qualified_name) = postgres_relation(pool, schema, name).await?;
+    if relkind != "i" && relkind != "I" {
+        return Err(format!("{qualified_name} 不是索引"));
+    }
+
+    let definition: String = sqlx::query_scalar("SELECT pg_catalog.pg_get_indexdef($1)")
+        .bind(oid)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("查詢索引定義錯誤: {e}"))?;
+
+    Ok(format!("{definition};"))
+}
+
+async fn postgres_trigger_ddl(pool: &sqlx::PgPool, schema: &str, name: &str) -> Result<String, String> {
+    let definitions: Vec<String> = sqlx::query_scalar(
+        "SELECT pg_catalog.pg_get_triggerdef(t.oid, true)
+         FROM pg_catalog.pg_trigger t
+         JOIN pg_catalog.pg_class c ON c.oid = t.tgrelid
+         JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+         WHERE n.nspname = $1 AND t.tgname = $2 AND NOT t.tgisinternal"
+    )
+        .bind(schema)
+        .bind(name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查詢觸發器定義錯誤: {e}"))?;
+
+    if definitions.is_empty() {
+        return Err(format!("找不到觸發器: {schema}.{name}"));
+    }
+
+    Ok(definitions.iter().map(|definition| format!("{definition};")).collect::<Vec<_>>().join("\n\n"))
+}
+
+async fn postgres_function_ddl(pool: &sqlx::PgPool, schema: &str, name: &str) -> Result<String, String> {
+    // 同名函數可能有多個重載版本，全部返回
+    let definitions: Vec<String> = sqlx::query_scalar(
+        "SELECT pg_catalog.pg_get_functiondef(p.oid)
+         FROM pg_catalog.pg_proc p
+         JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+         WHERE n.nspname = $1 AND p.proname = $2 AND p.prokind IN ('f', 'p', 'w')
+         ORDER BY p.oid"
+    )
+        .bind(schema)
+        .bind(name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查詢函數定義錯誤: {e}"))?;
+
+    if definitions.is_empty() {
+        return Err(format!("找不到函數: {schema}.{name}"));
+    }
+
+    Ok(definitions.iter().map(|definition| format!("{};", definition.trim_end())).collect::<Vec<_>>().join("\n\n"))
+}
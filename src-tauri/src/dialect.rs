@@ -0,0 +1,111 @@
+use serde_json::Value;
+
+use crate::binary::{binary_bytes, to_hex};
+use crate::spatial::geometry_hex;
+
+// 依資料庫方言為識別字加上引號
+pub(crate) fn quote_identifier(db_type: &str, name: &str) -> String {
+    match db_type {
+        "mysql" => {
+            let escaped = name.replace('`', "``");
+            format!("`{escaped}`")
+        }
+        _ => {
+            let escaped = name.replace('"', "\"\"");
+            format!("\"{escaped}\"")
+        }
+    }
+}
+
+pub(crate) fn quote_string(db_type: &str, text: &str) -> String {
+    match db_type {
+        // MySQL 預設把反斜線視為跳脫字元
+        "mysql" => {
+            let escaped = text.replace('\\', "\\\\").replace('\'', "''");
+            format!("'{escaped}'")
+        }
+        _ => {
+            let escaped = text.replace('\'', "''");
+            format!("'{escaped}'")
+        }
+    }
+}
+
+// 將 JSON 值轉為可直接放進 INSERT 語句的 SQL 字面值
+pub(crate) fn sql_literal(db_type: &str, value: &Value) -> String {
+    // 幾何以 WKB 建構，保留 SRID
+    if let Some((hex, srid)) = geometry_hex(value) {
+        return match (db_type, srid) {
+            ("mysql", Some(0)) => format!("ST_GeomFromWKB(X'{hex}', 0)"),
+            // MySQL 8 預設依 SRS 的軸順序讀取 WKB，4326 等地理座標系統是緯度在前；內部格式是經度在前
+            ("mysql", Some(srid)) => format!("ST_GeomFromWKB(X'{hex}', {srid}, 'axis-order=long-lat')"),
+            ("postgresql", Some(srid)) => format!("ST_GeomFromWKB('\\x{hex}', {srid})"),
+            ("postgresql", None) => format!("ST_GeomFromEWKB('\\x{hex}')"),
+            _ => format!("X'{hex}'"),
+        };
+    }
+    if let Some(bytes) = binary_bytes(value) {
+        let hex = to_hex(&bytes);
+        return match db_type {
+            "postgresql" => format!("'\\x{hex}'"),
+            _ => format!("X'{hex}'"),
+        };
+    }
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Bool(b) => match (db_type, b) {
+            ("sqlite", true) => "1".to_string(),
+            ("sqlite", false) => "0".to_string(),
+            (_, true) => "TRUE".to_string(),
+            (_, false) => "FALSE".to_string(),
+        },
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => quote_string(db_type, s),
+        Value::Array(_) | Value::Object(_) => quote_string(db_type, &value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spatial::{mysql_geojson, mysql_geometry_value};
+
+    // MySQL 內部格式的 SRID 4326 點 (經度 121.5, 緯度 25.0)
+    fn mysql_point_4326() -> Vec<u8> {
+        let mut bytes = 4326u32.to_le_bytes().to_vec();
+        bytes.push(1);
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend(121.5f64.to_le_bytes());
+        bytes.extend(25.0f64.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn mysql_geometry_round_trip_keeps_axis_order() {
+        let bytes = mysql_point_4326();
+        let value = mysql_geometry_value(&bytes).unwrap();
+        let wkb = to_hex(&bytes[4..]);
+        assert_eq!(sql_literal("mysql", &value), format!("ST_GeomFromWKB(X'{wkb}', 4326, 'axis-order=long-lat')"));
+
+        // 還原後的資料與原本的內部格式相同，GeoJSON 仍是經度在前
+        let (hex, srid) = geometry_hex(&value).unwrap();
+        assert_eq!(srid, Some(4326));
+        assert_eq!(hex, wkb);
+        let geojson = mysql_geojson(&bytes).unwrap();
+        assert_eq!(geojson["coordinates"], serde_json::json!([121.5, 25.0]));
+    }
+
+    #[test]
+    fn mysql_geometry_without_srid() {
+        let mut bytes = mysql_point_4326();
+        bytes[..4].copy_from_slice(&0u32.to_le_bytes());
+        let value = mysql_geometry_value(&bytes).unwrap();
+        assert!(sql_literal("mysql", &value).ends_with(", 0)"));
+    }
+
+    #[test]
+    fn postgres_geometry_literal() {
+        let value = crate::spatial::geometry_value(&[1, 2], None);
+        assert_eq!(sql_literal("postgresql", &value), "ST_GeomFromEWKB('\\x0102')");
+    }
+}
@@ -0,0 +1,818 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Row;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::connection::{connect_mysql, connect_postgres, connect_sqlite};
+use crate::ddl::{mysql_text_column, postgres_foreign_key_ddl, postgres_table_ddl, postgres_view_ddl};
+use crate::dialect::{quote_identifier, quote_string, sql_literal};
+use crate::export::value_text;
+use crate::query::{stream_rows, ResultColumn, ResultFormat, RowHandler};
+use crate::values::{mysql_export_values, postgres_export_values, sqlite_export_values};
+use crate::DatabaseConnection;
+
+// 每匯出多少行發送一次進度事件
+const PROGRESS_INTERVAL: u64 = 5000;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DumpRequest {
+    pub connection: DatabaseConnection,
+    pub file_path: Option<String>, // 未指定時開啟儲存對話框
+    #[serde(default)]
+    pub options: DumpOptions,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DumpOptions {
+    pub schema: Option<String>, // PostgreSQL 預設為 public，MySQL 預設為目前的資料庫
+    pub tables: Option<Vec<String>>, // 未指定時匯出全部表格
+    pub schema_only: bool,
+    pub data_only: bool,
+    pub where_clause: Option<String>, // 套用到每個表格的資料篩選條件
+    pub table_where: HashMap<String, String>, // 個別表格的篩選條件，優先於 where_clause
+    pub data_format: String, // 'insert', 'copy'（僅 PostgreSQL，需以 psql 還原）
+    pub rows_per_insert: usize,
+    pub drop_objects: bool, // 建立前先 DROP ... IF EXISTS
+    pub include_views: bool,
+}
+
+impl Default for DumpOptions {
+    fn default() -> Self {
+        Self {
+            schema: None,
+            tables: None,
+            schema_only: false,
+            data_only: false,
+            where_clause: None,
+            table_where: HashMap::new(),
+            data_format: "insert".to_string(),
+            rows_per_insert: 100,
+            drop_objects: false,
+            include_views: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DumpResult {
+    pub success: bool,
+    pub file_path: Option<String>,
+    pub tables_dumped: usize,
+    pub rows_dumped: u64,
+    pub execution_time: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DumpProgress {
+    pub file_path: String,
+    pub table: String,
+    pub tables_dumped: usize,
+    pub table_count: usize,
+    pub rows_dumped: u64,
+}
+
+#[tauri::command]
+pub async fn dump_database(app: AppHandle, request: DumpRequest) -> Result<DumpResult, String> {
+    let start_time = std::time::Instant::now();
+
+    let path = match &request.file_path {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => match choose_dump_path(&app, &request.connection).await? {
+            Some(path) => path,
+            None => {
+                return Ok(DumpResult {
+                    success: false,
+                    file_path: None,
+                    tables_dumped: 0,
+                    rows_dumped: 0,
+                    execution_time: 0,
+                    message: "已取消匯出".to_string(),
+                })
+            }
+        },
+    };
+    let file_path = path.to_string_lossy().into_owned();
+
+    let mut context = DumpContext {
+        app: app.clone(),
+        file_path: file_path.clone(),
+        out: None,
+        options: &request.options,
+        table_count: 0,
+        tables_dumped: 0,
+        rows_dumped: 0,
+    };
+    let result = dump_to_file(&request, &path, &mut context).await;
+    let execution_time = start_time.elapsed().as_millis() as u64;
+    let (tables_dumped, rows_dumped) = (context.tables_dumped, context.rows_dumped);
+
+    match result {
+        Ok(()) => Ok(DumpResult {
+            success: true,
+            file_path: Some(file_path.clone()),
+            tables_dumped,
+            rows_dumped,
+            execution_time,
+            message: format!("匯出成功，共 {tables_dumped} 個表格、{rows_dumped} 行至 {file_path}"),
+        }),
+        Err(error) => Ok(DumpResult {
+            success: false,
+            file_path: Some(file_path),
+            tables_dumped,
+            rows_dumped,
+            execution_time,
+            message: format!("匯出失敗: {error}"),
+        }),
+    }
+}
+
+async fn choose_dump_path(app: &AppHandle, connection: &DatabaseConnection) -> Result<Option<PathBuf>, String> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+
+    // SQLite 的 database 是檔案路徑，只取檔名
+    let database_name = PathBuf::from(&connection.database)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or_else(|| "dump".to_string());
+
+    app.dialog()
+        .file()
+        .add_filter("SQL", &["sql"])
+        .set_file_name(format!("{database_name}.sql"))
+        .save_file(move |path| {
+            let _ = sender.send(path);
+        });
+
+    match receiver.await.map_err(|e| format!("儲存對話框錯誤: {e}"))? {
+        Some(path) => path.into_path().map(Some).map_err(|e| format!("無效的檔案路徑: {e}")),
+        None => Ok(None),
+    }
+}
+
+async fn dump_to_file(request: &DumpRequest, path: &PathBuf, context: &mut DumpContext<'_>) -> Result<(), String> {
+    let options = &request.options;
+    let db_type = request.connection.db_type.as_str();
+    if options.schema_only && options.data_only {
+        return Err("schema_only 與 data_only 不能同時使用".to_string());
+    }
+    match (options.data_format.as_str(), db_type) {
+        ("insert", _) | ("copy", "postgresql") => {}
+        ("copy", _) => return Err("COPY 格式只支援 PostgreSQL".to_string()),
+        (other, _) => return Err(format!("不支援的資料格式: {other}")),
+    }
+
+    let file = File::create(path).map_err(|e| format!("無法建立檔案: {e}"))?;
+    context.out = Some(BufWriter::new(file));
+    context.write(&format!("-- Serphic {db_type} 資料庫匯出\n\n"))?;
+
+    match db_type {
+        "sqlite" => {
+            let pool = connect_sqlite(&request.connection).await?;
+            let result = dump_sqlite(&pool, context).await;
+            pool.close().await;
+            result?;
+        }
+        "mysql" => {
+            let pool = connect_mysql(&request.connection).await?;
+            let result = dump_mysql(&pool, context).await;
+            pool.close().await;
+            result?;
+        }
+        "postgresql" => {
+            let pool = connect_postgres(&request.connection).await?;
+            let result = dump_postgres(&pool, context).await;
+            pool.close().await;
+            result?;
+        }
+        _ => return Err("不支援的資料庫類型".to_string()),
+    }
+
+    if let Some(out) = context.out.as_mut() {
+        out.flush().map_err(|e| format!("寫入檔案錯誤: {e}"))?;
+    }
+    Ok(())
+}
+
+// 單一表格要匯出的資料
+struct TableData<'a> {
+    name: &'a str,
+    source_name: String, // 查詢資料用的名稱
+    target_name: String, // INSERT 或 COPY 的目標名稱
+    columns: Vec<String>,
+    overriding_system_value: bool, // PostgreSQL 的 GENERATED ALWAYS 識別欄位
+}
+
+struct DumpContext<'a> {
+    app: AppHandle,
+    file_path: String,
+    out: Option<BufWriter<File>>,
+    options: &'a DumpOptions,
+    table_count: usize,
+    tables_dumped: usize,
+    rows_dumped: u64,
+}
+
+impl DumpContext<'_> {
+    fn write(&mut self, text: &str) -> Result<(), String> {
+        let out = self.out.as_mut().ok_or("匯出檔案尚未建立")?;
+        out.write_all(text.as_bytes()).map_err(|e| format!("寫入檔案錯誤: {e}"))
+    }
+
+    fn include_schema(&self) -> bool {
+        !self.options.data_only
+    }
+
+    fn include_data(&self) -> bool {
+        !self.options.schema_only
+    }
+
+    // 依表格篩選條件挑出要匯出的表格，保留原本的順序
+    fn select_tables(&mut self, available: Vec<(String, String)>) -> Result<Vec<(String, String)>, String> {
+        let selected = match &self.options.tables {
+            Some(tables) if !tables.is_empty() => {
+                if let Some(missing) = tables.iter().find(|table| !available.iter().any(|(name, _)| name == *table)) {
+                    return Err(format!("找不到表格: {missing}"));
+                }
+                available.into_iter().filter(|(name, _)| tables.contains(name)).collect()
+            }
+            _ => available,
+        };
+        self.table_count = selected.len();
+        Ok(selected)
+    }
+
+    fn where_clause(&self, table: &str) -> String {
+        match self.options.table_where.get(table).or(self.options.where_clause.as_ref()) {
+            Some(condition) if !condition.trim().is_empty() => format!(" WHERE {condition}"),
+            _ => String::new(),
+        }
+    }
+
+    fn emit_progress(&self, table: &str) {
+        let _ = self.app.emit("dump-progress", DumpProgress {
+            file_path: self.file_path.clone(),
+            table: table.to_string(),
+            tables_dumped: self.tables_dumped,
+            table_count: self.table_count,
+            rows_dumped: self.rows_dumped,
+        });
+    }
+
+    async fn table_data<DB>(
+        &mut self,
+        pool: &sqlx::Pool<DB>,
+        db_type: &str,
+        decode: fn(&DB::Row) -> Vec<Value>,
+        table: &TableData<'_>,
+    ) -> Result<(), String>
+    where
+        DB: ResultFormat,
+        for<'c> &'c mut DB::Connection: sqlx::Executor<'c, Database = DB>,
+        for<'q> DB::Arguments<'q>: sqlx::IntoArguments<'q, DB>,
+    {
+        if table.columns.is_empty() {
+            return Ok(());
+        }
+
+        let column_list = table.columns
+            .iter()
+            .map(|column| quote_identifier(db_type, column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!("SELECT {column_list} FROM {}{}", table.source_name, self.where_clause(table.name));
+
+        let copy = self.options.data_format == "copy";
+        let mut writer = TableDataWriter {
+            context: self,
+            db_type,
+            table: table.name,
+            insert_prefix: if table.overriding_system_value {
+                format!("INSERT INTO {} ({column_list}) OVERRIDING SYSTEM VALUE VALUES\n", table.target_name)
+            } else {
+                format!("INSERT INTO {} ({column_list}) VALUES\n", table.target_name)
+            },
+            copy_header: copy.then(|| format!("COPY {} ({column_list}) FROM stdin;\n", table.target_name)),
+            pending_rows: 0,
+        };
+
+        stream_rows(pool, &sql, decode, &mut writer).await?;
+        writer.finish()?;
+        self.write("\n")
+    }
+}
+
+// 以多行 INSERT 或 COPY 區塊寫出表格資料
+struct TableDataWriter<'a, 'b> {
+    context: &'a mut DumpContext<'b>,
+    db_type: &'a str,
+    table: &'a str,
+    insert_prefix: String,
+    copy_header: Option<String>,
+    pending_rows: usize,
+}
+
+impl TableDataWriter<'_, '_> {
+    fn finish(&mut self) -> Result<(), String> {
+        if self.copy_header.is_some() {
+            return self.context.write("\\.\n");
+        }
+        if self.pending_rows > 0 {
+            self.pending_rows = 0;
+            return self.context.write(";\n");
+        }
+        Ok(())
+    }
+}
+
+impl RowHandler for TableDataWriter<'_, '_> {
+    fn on_columns(&mut self, _columns: &[ResultColumn]) -> Result<(), String> {
+        match self.copy_header.clone() {
+            Some(header) => self.context.write(&header),
+            None => Ok(()),
+        }
+    }
+
+    fn on_row(&mut self, values: Vec<Value>) -> Result<(), String> {
+        if self.copy_header.is_some() {
+            let line = values.iter().map(copy_field).collect::<Vec<_>>().join("\t");
+            self.context.write(&format!("{line}\n"))?;
+        } else {
+            let tuple = values
+                .iter()
+                .map(|value| sql_literal(self.db_type, value))
+                .collect::<Vec<_>>()
+                .join(", ");
+            if self.pending_rows == 0 {
+                let prefix = self.insert_prefix.clone();
+                self.context.write(&prefix)?;
+            } else {
+                self.context.write(",\n")?;
+            }
+            self.context.write(&format!("({tuple})"))?;
+
+            self.pending_rows += 1;
+            if self.pending_rows >= self.context.options.rows_per_insert.max(1) {
+                self.context.write(";\n")?;
+                self.pending_rows = 0;
+            }
+        }
+
+        self.context.rows_dumped += 1;
+        if self.context.rows_dumped.is_multiple_of(PROGRESS_INTERVAL) {
+            self.context.emit_progress(self.table);
+        }
+        Ok(())
+    }
+}
+
+// COPY 文字格式：NULL 為 \N，反斜線與控制字元需跳脫
+fn copy_field(value: &Value) -> String {
+    match value {
+        Value::Null => "\\N".to_string(),
+        other => value_text(other)
+            .replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r"),
+    }
+}
+
+async fn dump_sqlite(pool: &sqlx::SqlitePool, context: &mut DumpContext<'_>) -> Result<(), String> {
+    // 檢視表依建立順序（rowid）輸出，被參照的檢視表一定較早建立
+    let rows = sqlx::query(
+        "SELECT name, type, sql FROM sqlite_master
+         WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' AND sql IS NOT NULL
+         ORDER BY CASE WHEN type = 'view' THEN rowid END, name"
+    )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查詢 sqlite_master 錯誤: {e}"))?;
+
+    let mut tables = Vec::new();
+    let mut views = Vec::new();
+    for row in &rows {
+        let name: String = row.try_get("name").map_err(|e| format!("取得物件名稱錯誤: {e}"))?;
+        let object_type: String = row.try_get("type").map_err(|e| format!("取得物件類型錯誤: {e}"))?;
+        let sql: String = row.try_get("sql").map_err(|e| format!("取得定義錯誤: {e}"))?;
+        if object_type == "table" {
+            tables.push((name, sql));
+        } else {
+            views.push((name, sql));
+        }
+    }
+    let tables = context.select_tables(tables)?;
+
+    context.write("PRAGMA foreign_keys = OFF;\nBEGIN TRANSACTION;\n\n")?;
+
+    for (name, sql) in &tables {
+        context.emit_progress(name);
+        let qualified_name = quote_identifier("sqlite", name);
+
+        if context.include_schema() {
+            if context.options.drop_objects {
+                context.write(&format!("DROP TABLE IF EXISTS {qualified_name};\n"))?;
+            }
+            context.write(&format!("{sql};\n\n"))?;
+        }
+
+        if context.include_data() {
+            // 產生欄位（hidden 為 2、3）不能寫入
+            let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_xinfo(?) WHERE hidden = 0 ORDER BY cid")
+                .bind(name)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| format!("查詢欄位資訊錯誤: {e}"))?;
+            let table = TableData {
+                name,
+                source_name: qualified_name.clone(),
+                target_name: qualified_name,
+                columns,
+                overriding_system_value: false,
+            };
+            context.table_data(pool, "sqlite", sqlite_export_values, &table).await?;
+        }
+
+        // 索引與觸發器在資料之後建立，避免觸發器作用在匯入的資料上
+        if context.include_schema() {
+            let definitions: Vec<String> = sqlx::query_scalar(
+                "SELECT sql FROM sqlite_master
+                 WHERE tbl_name = ? AND type IN ('index', 'trigger') AND sql IS NOT NULL
+                 ORDER BY type, name"
+            )
+                .bind(name)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| format!("查詢索引與觸發器錯誤: {e}"))?;
+            for definition in definitions {
+                context.write(&format!("{definition};\n"))?;
+            }
+        }
+
+        context.tables_dumped += 1;
+    }
+
+    if context.include_schema() && context.options.include_views {
+        for (name, sql) in &views {
+            if context.options.drop_objects {
+                context.write(&format!("DROP VIEW IF EXISTS {};\n", quote_identifier("sqlite", name)))?;
+            }
+            context.write(&format!("{sql};\n\n"))?;
+        }
+    }
+
+    context.write("COMMIT;\n")?;
+    context.emit_progress("");
+    Ok(())
+}
+
+async fn dump_mysql(pool: &sqlx::MySqlPool, context: &mut DumpContext<'_>) -> Result<(), String> {
+    let schema = context.options.schema.clone().filter(|schema| !schema.is_empty());
+    let qualify = |name: &str| match &schema {
+        Some(schema) => format!("{}.{}", quote_identifier("mysql", schema), quote_identifier("mysql", name)),
+        None => quote_identifier("mysql", name),
+    };
+
+    let rows = sqlx::query(
+        "SELECT TABLE_NAME, TABLE_TYPE FROM information_schema.TABLES
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE())
+         ORDER BY TABLE_NAME"
+    )
+        .bind(&schema)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查詢表格列表錯誤: {e}"))?;
+
+    let mut tables = Vec::new();
+    let mut views = Vec::new();
+    for row in &rows {
+        let name = mysql_text_column(row, 0)?;
+        let table_type = mysql_text_column(row, 1)?;
+        if table_type == "VIEW" {
+            views.push(name);
+        } else {
+            tables.push((name, table_type));
+        }
+    }
+    let tables = context.select_tables(tables)?;
+
+    // 與 mysqldump 相同：停用外鍵檢查，並保留自動編號欄位中的 0
+    context.write("SET NAMES utf8mb4;\nSET FOREIGN_KEY_CHECKS = 0;\nSET SQL_MODE = 'NO_AUTO_VALUE_ON_ZERO';\n\n")?;
+
+    for (name, _) in &tables {
+        context.emit_progress(name);
+        // 輸出不帶資料庫名稱，方便還原到其他資料庫
+        let output_name = quote_identifier("mysql", name);
+
+        if context.include_schema() {
+            let row = sqlx::raw_sql(&format!("SHOW CREATE TABLE {}", qualify(name)))
+                .fetch_one(pool)
+                .await
+                .map_err(|e| format!("查詢表格定義錯誤: {e}"))?;
+            if context.options.drop_objects {
+                context.write(&format!("DROP TABLE IF EXISTS {output_name};\n"))?;
+            }
+            context.write(&format!("{};\n\n", mysql_text_column(&row, 1)?))?;
+        }
+
+        if context.include_data() {
+            let column_rows = sqlx::query(
+                "SELECT COLUMN_NAME FROM information_schema.COLUMNS
+                 WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? AND EXTRA NOT LIKE '%GENERATED%'
+                 ORDER BY ORDINAL_POSITION"
+            )
+                .bind(&schema)
+                .bind(name)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| format!("查詢欄位資訊錯誤: {e}"))?;
+            let columns = column_rows.iter().map(|row| mysql_text_column(row, 0)).collect::<Result<Vec<_>, _>>()?;
+
+            let table = TableData {
+                name,
+                source_name: qualify(name),
+                target_name: output_name.clone(),
+                columns,
+                overriding_system_value: false,
+            };
+            context.table_data(pool, "mysql", mysql_export_values, &table).await?;
+        }
+
+        if context.include_schema() {
+            let trigger_rows = sqlx::query(
+                "SELECT TRIGGER_NAME FROM information_schema.TRIGGERS
+                 WHERE EVENT_OBJECT_SCHEMA = COALESCE(?, DATABASE()) AND EVENT_OBJECT_TABLE = ?
+                 ORDER BY ACTION_TIMING, EVENT_MANIPULATION, ACTION_ORDER"
+            )
+                .bind(&schema)
+                .bind(name)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| format!("查詢觸發器錯誤: {e}"))?;
+
+            for trigger_row in &trigger_rows {
+                let trigger = mysql_text_column(trigger_row, 0)?;
+                let row = sqlx::raw_sql(&format!("SHOW CREATE TRIGGER {}", qualify(&trigger)))
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| format!("查詢觸發器定義錯誤: {e}"))?;
+                // 觸發器內含分號，與 mysqldump 相同以 DELIMITER 包住
+                context.write(&format!("DELIMITER ;;\n{};;\nDELIMITER ;\n\n", mysql_text_column(&row, 2)?))?;
+            }
+        }
+
+        context.tables_dumped += 1;
+    }
+
+    if context.include_schema() && context.options.include_views {
+        // VIEW_TABLE_USAGE 只在 MySQL 8.0.13 之後提供，查詢失敗時維持名稱順序
+        let dependencies: Vec<(String, String)> = sqlx::query(
+            "SELECT VIEW_NAME, TABLE_NAME FROM information_schema.VIEW_TABLE_USAGE
+             WHERE VIEW_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_SCHEMA = VIEW_SCHEMA"
+        )
+            .bind(&schema)
+            .fetch_all(pool)
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|row| Some((mysql_text_column(row, 0).ok()?, mysql_text_column(row, 1).ok()?)))
+            .collect();
+        let views = dependency_order(views, |name: &String| name.as_str(), &dependencies);
+        for name in &views {
+            let row = sqlx::raw_sql(&format!("SHOW CREATE VIEW {}", qualify(name)))
+                .fetch_one(pool)
+                .await
+                .map_err(|e| format!("查詢檢視表定義錯誤: {e}"))?;
+            if context.options.drop_objects {
+                context.write(&format!("DROP VIEW IF EXISTS {};\n", quote_identifier("mysql", name)))?;
+            }
+            context.write(&format!("{};\n\n", mysql_text_column(&row, 1)?))?;
+        }
+    }
+
+    context.write("SET FOREIGN_KEY_CHECKS = 1;\n")?;
+    context.emit_progress("");
+    Ok(())
+}
+
+async fn dump_postgres(pool: &sqlx::PgPool, context: &mut DumpContext<'_>) -> Result<(), String> {
+    let schema = context.options.schema.clone()
+        .filter(|schema| !schema.is_empty())
+        .unwrap_or_else(|| "public".to_string());
+
+    let rows = sqlx::query(
+        "SELECT c.relname, c.relkind::text AS relkind
+         FROM pg_catalog.pg_class c
+         JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+         WHERE n.nspname = $1 AND c.relkind IN ('r', 'p', 'v', 'm')
+         ORDER BY c.relname"
+    )
+        .bind(&schema)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查詢表格列表錯誤: {e}"))?;
+
+    let mut tables = Vec::new();
+    let mut views = Vec::new();
+    for row in &rows {
+        let name: String = row.try_get("relname").map_err(|e| format!("取得物件名稱錯誤: {e}"))?;
+        let relkind: String = row.try_get("relkind").map_err(|e| format!("取得物件類型錯誤: {e}"))?;
+        match relkind.as_str() {
+            "v" | "m" => views.push((name, relkind)),
+            _ => tables.push((name, relkind)),
+        }
+    }
+    let tables = context.select_tables(tables)?;
+
+    context.write("SET client_encoding = 'UTF8';\nSET standard_conforming_strings = on;\n\n")?;
+    if context.include_schema() && schema != "public" {
+        context.write(&format!("CREATE SCHEMA IF NOT EXISTS {};\n\n", quote_identifier("postgresql", &schema)))?;
+    }
+
+    for (name, relkind) in &tables {
+        context.emit_progress(name);
+        let qualified_name = format!("{}.{}", quote_identifier("postgresql", &schema), quote_identifier("postgresql", name));
+        let sequences = postgres_owned_sequences(pool, &schema, name).await?;
+
+        if context.include_schema() {
+            if context.options.drop_objects {
+                context.write(&format!("DROP TABLE IF EXISTS {qualified_name} CASCADE;\n"))?;
+            }
+            // serial 欄位的預設值參照序列，需先建立序列
+            for sequence in sequences.iter().filter(|sequence| !sequence.identity) {
+                context.write(&format!("CREATE SEQUENCE IF NOT EXISTS {};\n", sequence.name))?;
+            }
+            // 外鍵在所有資料匯入後才建立，表格順序不受參照關係影響
+            let ddl = postgres_table_ddl(pool, &schema, name, false).await?;
+            context.write(&format!("{ddl}\n"))?;
+            for sequence in sequences.iter().filter(|sequence| !sequence.identity) {
+                context.write(&format!(
+                    "ALTER SEQUENCE {} OWNED BY {qualified_name}.{};\n",
+                    sequence.name,
+                    quote_identifier("postgresql", &sequence.column)
+                ))?;
+            }
+            context.write("\n")?;
+        }
+
+        // 分區主表本身沒有資料，資料由各分區匯出
+        if context.include_data() && relkind != "p" {
+            let column_rows = sqlx::query(
+                "SELECT a.attname::text AS column_name, a.attidentity::text AS identity
+                 FROM pg_catalog.pg_attribute a
+                 WHERE a.attrelid = format('%I.%I', $1::text, $2::text)::regclass
+                   AND a.attnum > 0 AND NOT a.attisdropped AND a.attgenerated = ''
+                 ORDER BY a.attnum"
+            )
+                .bind(&schema)
+                .bind(name)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| format!("查詢欄位資訊錯誤: {e}"))?;
+
+            let mut columns = Vec::with_capacity(column_rows.len());
+            let mut overriding_system_value = false;
+            for row in &column_rows {
+                columns.push(row.try_get::<String, _>("column_name").map_err(|e| format!("取得欄位名稱錯誤: {e}"))?);
+                overriding_system_value |= row.try_get::<String, _>("identity").unwrap_or_default() == "a";
+            }
+
+            let table = TableData {
+                name,
+                source_name: qualified_name.clone(),
+                target_name: qualified_name.clone(),
+                columns,
+                overriding_system_value,
+            };
+            context.table_data(pool, "postgresql", postgres_export_values, &table).await?;
+
+            // 還原序列目前的值，避免之後新增的資料與匯入的主鍵衝突
+            for sequence in &sequences {
+                let row = sqlx::query(&format!("SELECT last_value, is_called FROM {}", sequence.name))
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| format!("查詢序列錯誤: {e}"))?;
+                let last_value: i64 = row.try_get("last_value").map_err(|e| format!("取得序列值錯誤: {e}"))?;
+                let is_called: bool = row.try_get("is_called").unwrap_or(true);
+                context.write(&format!(
+                    "SELECT pg_catalog.setval(pg_catalog.pg_get_serial_sequence({}, {}), {last_value}, {is_called});\n\n",
+                    quote_string("postgresql", &qualified_name),
+                    quote_string("postgresql", &sequence.column)
+                ))?;
+            }
+        }
+
+        context.tables_dumped += 1;
+    }
+
+    if context.include_schema() {
+        for (name, _) in &tables {
+            for statement in postgres_foreign_key_ddl(pool, &schema, name).await? {
+                context.write(&format!("{statement}\n"))?;
+            }
+        }
+        context.write("\n")?;
+
+        if context.options.include_views {
+            // 檢視表的 _RETURN 規則相依於它所參照的關聯
+            let dependencies: Vec<(String, String)> = sqlx::query_as(
+                "SELECT DISTINCT v.relname::text, r.relname::text
+                 FROM pg_catalog.pg_depend d
+                 JOIN pg_catalog.pg_rewrite w ON w.oid = d.objid
+                 JOIN pg_catalog.pg_class v ON v.oid = w.ev_class
+                 JOIN pg_catalog.pg_class r ON r.oid = d.refobjid
+                 JOIN pg_catalog.pg_namespace n ON n.oid = v.relnamespace
+                 WHERE d.classid = 'pg_catalog.pg_rewrite'::regclass
+                   AND d.refclassid = 'pg_catalog.pg_class'::regclass
+                   AND n.nspname = $1 AND r.relnamespace = v.relnamespace AND r.oid <> v.oid"
+            )
+                .bind(&schema)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| format!("查詢檢視表相依關係錯誤: {e}"))?;
+            let views = dependency_order(views, |(name, _): &(String, String)| name.as_str(), &dependencies);
+            for (name, relkind) in &views {
+                if context.options.drop_objects {
+                    let kind = if relkind == "m" { "MATERIALIZED VIEW" } else { "VIEW" };
+                    let qualified_name = format!("{}.{}", quote_identifier("postgresql", &schema), quote_identifier("postgresql", name));
+                    context.write(&format!("DROP {kind} IF EXISTS {qualified_name} CASCADE;\n"))?;
+                }
+                let ddl = postgres_view_ddl(pool, &schema, name).await?;
+                context.write(&format!("{ddl}\n\n"))?;
+            }
+        }
+    }
+
+    context.emit_progress("");
+    Ok(())
+}
+
+// 依相依關係排序檢視表，(檢視表, 參照的物件) 中參照的檢視表先輸出
+// 沒有相依關係的檢視表維持原本的順序，循環參照時從第一個尚未輸出的繼續
+fn dependency_order<T>(views: Vec<T>, name: fn(&T) -> &str, dependencies: &[(String, String)]) -> Vec<T> {
+    let names: Vec<&str> = views.iter().map(name).collect();
+    let mut emitted = vec![false; views.len()];
+    let mut order = Vec::with_capacity(views.len());
+
+    while order.len() < views.len() {
+        let ready = (0..views.len()).find(|&i| {
+            !emitted[i]
+                && dependencies
+                    .iter()
+                    .filter(|(view, _)| view == names[i])
+                    .all(|(_, referenced)| (0..views.len()).all(|j| j == i || emitted[j] || names[j] != referenced))
+        });
+        let next = ready.or_else(|| emitted.iter().position(|done| !done)).unwrap_or(0);
+        emitted[next] = true;
+        order.push(next);
+    }
+
+    let mut slots: Vec<Option<T>> = views.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+struct OwnedSequence {
+    name: String, // 帶引號的完整名稱
+    column: String,
+    identity: bool,
+}
+
+// 表格欄位擁有的序列：serial 欄位（deptype 'a'）與識別欄位（deptype 'i'）
+async fn postgres_owned_sequences(pool: &sqlx::PgPool, schema: &str, name: &str) -> Result<Vec<OwnedSequence>, String> {
+    let rows = sqlx::query(
+        "SELECT format('%I.%I', sn.nspname, s.relname) AS sequence_name,
+                a.attname::text AS column_name,
+                d.deptype = 'i' AS identity
+         FROM pg_catalog.pg_depend d
+         JOIN pg_catalog.pg_class s ON s.oid = d.objid AND s.relkind = 'S'
+         JOIN pg_catalog.pg_namespace sn ON sn.oid = s.relnamespace
+         JOIN pg_catalog.pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+         WHERE d.classid = 'pg_catalog.pg_class'::regclass
+           AND d.refobjid = format('%I.%I', $1::text, $2::text)::regclass
+           AND d.deptype IN ('a', 'i')
+         ORDER BY a.attnum"
+    )
+        .bind(schema)
+        .bind(name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查詢序列資訊錯誤: {e}"))?;
+
+    rows.iter()
+        .map(|row| {
+            Ok(OwnedSequence {
+                name: row.try_get("sequence_name").map_err(|e| format!("取得序列名稱錯誤: {e}"))?,
+                column: row.try_get("column_name").map_err(|e| format!("取得欄位名稱錯誤: {e}"))?,
+                identity: row.try_get("identity").unwrap_or(false),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::Row;
+
+use crate::connection::{connect_mysql, connect_postgres, connect_sqlite};
+use crate::sql_guard::{check_read_only, reject_risky};
+use crate::sql_splitter::split_statements;
+use crate::DatabaseConnection;
+
+// MySQL JSON 計畫中代表一個操作的物件
+const MYSQL_PLAN_OBJECTS: &[&str] = &[
+    "query_block", "table", "ordering_operation", "grouping_operation", "duplicates_removal",
+    "union_result", "materialized_from_subquery", "windowing", "buffer_result",
+];
+
+// PostgreSQL 節點中轉為 detail 的條件
+const POSTGRES_CONDITIONS: &[&str] = &[
+    "Index Cond", "Recheck Cond", "Hash Cond", "Merge Cond", "Join Filter", "Filter",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExplainRequest {
+    pub connection: DatabaseConnection,
+    pub sql: String,
+    #[serde(default)]
+    pub analyze: bool, // 實際執行語句並取得時間與行數，PostgreSQL 會在交易中執行後回滾
+}
+
+// 三種資料庫共用的計畫節點，無法取得的數值為 None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanNode {
+    pub operation: String,
+    pub relation: Option<String>,
+    pub index: Option<String>,
+    pub detail: Option<String>,
+    pub startup_cost: Option<f64>,
+    pub total_cost: Option<f64>,
+    pub estimated_rows: Option<f64>,
+    pub actual_rows: Option<f64>,
+    pub actual_time_ms: Option<f64>, // 所有迴圈的總時間
+    pub loops: Option<f64>,
+    pub properties: Map<String, Value>, // 原始計畫中的其他欄位
+    pub children: Vec<PlanNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExplainResult {
+    pub success: bool,
+    pub plan: Option<PlanNode>,
+    pub raw: Value, // 資料庫返回的原始計畫
+    pub planning_time_ms: Option<f64>,
+    pub execution_time_ms: Option<f64>,
+    pub analyzed: bool,
+    pub execution_time: u64,
+    pub message: String,
+}
+
+#[tauri::command]
+pub async fn explain_query(request: ExplainRequest) -> Result<ExplainResult, String> {
+    let start_time = std::time::Instant::now();
+    let db_type = request.connection.db_type.as_str();
+
+    let mut statements = split_statements(&request.sql, db_type);
+    if statements.len() != 1 {
+        return Err("一次只能解釋一條語句".to_string());
+    }
+    let sql = statements.remove(0);
+    let sql = sql.trim_end_matches(';').trim();
+
+    // ANALYZE 會實際執行語句，唯讀連接只允許解釋讀取語句，受保護環境不允許高風險語句
+    if request.analyze {
+        check_read_only(&request.connection, &format!("EXPLAIN ANALYZE {sql}"))?;
+        reject_risky(&request.connection, sql, "以 ANALYZE 解釋時")?;
+    }
+
+    let result = match db_type {
+        "sqlite" => sqlite_plan(&request.connection, sql).await,
+        "mysql" => mysql_plan(&request.connection, sql).await,
+        "postgresql" => postgres_plan(&request.connection, sql, request.analyze).await,
+        _ => return Err("不支援的資料庫類型".to_string()),
+    };
+    let execution_time = start_time.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(explained) => {
+            let analyzed = request.analyze && db_type == "postgresql";
+            let message = if request.analyze && !analyzed {
+                "取得執行計畫成功（此資料庫不支援 ANALYZE，只返回估計值）".to_string()
+            } else {
+                "取得執行計畫成功".to_string()
+            };
+            Ok(ExplainResult {
+                success: true,
+                plan: Some(explained.plan),
+                raw: explained.raw,
+                planning_time_ms: explained.planning_time_ms,
+                execution_time_ms: explained.execution_time_ms,
+                analyzed,
+                execution_time,
+                message,
+            })
+        }
+        Err(error) => Ok(ExplainResult {
+            success: false,
+            plan: None,
+            raw: Value::Null,
+            planning_time_ms: None,
+            execution_time_ms: None,
+            analyzed: false,
+            execution_time,
+            message: format!("取得執行計畫失敗: {error}"),
+        }),
+    }
+}
+
+struct ExplainedPlan {
+    plan: PlanNode,
+    raw: Value,
+    planning_time_ms: Option<f64>,
+    execution_time_ms: Option<f64>,
+}
+
+async fn postgres_plan(connection: &DatabaseConnection, sql: &str, analyze: bool) -> Result<ExplainedPlan, String> {
+    let pool = connect_postgres(connection).await?;
+    let options = if analyze { "FORMAT JSON, ANALYZE, BUFFERS" } else { "FORMAT JSON" };
+    let explain = format!("EXPLAIN ({options}) {sql}");
+
+    // ANALYZE 的修改一律回滾
+    let result = async {
+        let mut tx = pool.begin().await.map_err(|e| format!("開始交易錯誤: {e}"))?;
+        let raw: Value = sqlx::query_scalar(&explain)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| format!("執行 EXPLAIN 錯誤: {e}"))?;
+        tx.rollback().await.map_err(|e| format!("回滾交易錯誤: {e}"))?;
+        Ok::<_, String>(raw)
+    }
+    .await;
+    pool.close().await;
+    let raw = result?;
+
+    let root = raw.get(0).ok_or("EXPLAIN 沒有返回計畫")?;
+    let plan = root.get("Plan").and_then(Value::as_object).ok_or("EXPLAIN 沒有返回計畫")?;
+    Ok(ExplainedPlan {
+        plan: postgres_node(plan),
+        planning_time_ms: root.get("Planning Time").and_then(Value::as_f64),
+        execution_time_ms: root.get("Execution Time").and_then(Value::as_f64),
+        raw,
+    })
+}
+
+fn postgres_node(node: &Map<String, Value>) -> PlanNode {
+    let text = |key: &str| node.get(key).and_then(Value::as_str).map(str::to_string);
+    let number = |key: &str| node.get(key).and_then(Value::as_f64);
+
+    let node_type = text("Node Type").unwrap_or_default();
+    let operation = match (text("Join Type"), text("Strategy")) {
+        (Some(join), _) if node_type.ends_with("Join") || node_type == "Nested Loop" => format!("{node_type} ({join})"),
+        (_, Some(strategy)) if node_type == "Aggregate" => format!("{node_type} ({strategy})"),
+        _ => node_type,
+    };
+    let relation = text("Relation Name").map(|name| match text("Schema") {
+        Some(schema) => format!("{schema}.{name}"),
+        None => name,
+    });
+    let conditions: Vec<String> = POSTGRES_CONDITIONS
+        .iter()
+        .filter_map(|key| text(key).map(|condition| format!("{key}: {condition}")))
+        .collect();
+
+    let loops = number("Actual Loops");
+    let properties = node
+        .iter()
+        .filter(|(key, _)| key.as_str() != "Plans")
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let children = node
+        .get("Plans")
+        .and_then(Value::as_array)
+        .map(|plans| plans.iter().filter_map(Value::as_object).map(postgres_node).collect())
+        .unwrap_or_default();
+
+    PlanNode {
+        operation,
+        relation,
+        index: text("Index Name"),
+        detail: (!conditions.is_empty()).then(|| conditions.join("\n")),
+        startup_cost: number("Startup Cost"),
+        total_cost: number("Total Cost"),
+        estimated_rows: number("Plan Rows"),
+        // Actual Rows 與 Actual Total Time 是每個迴圈的平均值
+        actual_rows: number("Actual Rows").map(|rows| rows * loops.unwrap_or(1.0)),
+        actual_time_ms: number("Actual Total Time").map(|time| time * loops.unwrap_or(1.0)),
+        loops,
+        properties,
+        children,
+    }
+}
+
+async fn mysql_plan(connection: &DatabaseConnection, sql: &str) -> Result<ExplainedPlan, String> {
+    let pool = connect_mysql(connection).await?;
+    let result = sqlx::query(&format!("EXPLAIN FORMAT=JSON {sql}"))
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| format!("執行 EXPLAIN 錯誤: {e}"));
+    pool.close().await;
+
+    let text: String = result?.try_get(0).map_err(|e| format!("讀取計畫錯誤: {e}"))?;
+    let raw: Value = serde_json::from_str(&text).map_err(|e| format!("解析計畫錯誤: {e}"))?;
+    let query_block = raw.get("query_block").and_then(Value::as_object).ok_or("EXPLAIN 沒有返回計畫")?;
+    Ok(ExplainedPlan {
+        plan: mysql_node("query_block", query_block),
+        raw,
+        planning_time_ms: None,
+        execution_time_ms: None,
+    })
+}
+
+fn mysql_number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(number) => number.as_f64(),
+        // cost_info 中的數值以字串表示
+        Value::String(text) => text.parse().ok(),
+        _ => None,
+    }
+}
+
+fn mysql_operation(key: &str, node: &Map<String, Value>) -> String {
+    let flag = |name: &str| node.get(name).and_then(Value::as_bool).unwrap_or(false);
+    match key {
+        "query_block" => "Query Block".to_string(),
+        "table" => match node.get("access_type").and_then(Value::as_str).unwrap_or_default() {
+            "ALL" => "Full Table Scan".to_string(),
+            "index" => "Full Index Scan".to_string(),
+            "range" => "Index Range Scan".to_string(),
+            "ref" | "eq_ref" | "ref_or_null" => "Index Lookup".to_string(),
+            "const" | "system" => "Constant Lookup".to_string(),
+            "" => "Table".to_string(),
+            other => format!("Table ({other})"),
+        },
+        "nested_loop" => "Nested Loop".to_string(),
+        "ordering_operation" if flag("using_filesort") => "Sort".to_string(),
+        "ordering_operation" => "Ordering".to_string(),
+        "grouping_operation" if flag("using_temporary_table") => "Group (Temporary Table)".to_string(),
+        "grouping_operation" => "Group".to_string(),
+        "duplicates_removal" => "Distinct".to_string(),
+        "union_result" => "Union".to_string(),
+        "materialized_from_subquery" => "Materialize".to_string(),
+        "windowing" => "Window".to_string(),
+        other => other.to_string(),
+    }
+}
+
+// MySQL 的計畫是巢狀物件：特定鍵代表子操作，物件陣列（nested_loop、attached_subqueries 等）代表一組子操作
+fn mysql_node(key: &str, node: &Map<String, Value>) -> PlanNode {
+    let text = |name: &str| node.get(name).and_then(Value::as_str).map(str::to_string);
+    let cost_info = node.get("cost_info").and_then(Value::as_object);
+    let cost = |name: &str| mysql_number(cost_info.and_then(|info| info.get(name)));
+
+    let mut properties = Map::new();
+    let mut children = Vec::new();
+    for (name, value) in node {
+        match value {
+            Value::Object(child) if MYSQL_PLAN_OBJECTS.contains(&name.as_str()) => children.push(mysql_node(name, child)),
+            Value::Array(items) if items.iter().all(Value::is_object) && !items.is_empty() => {
+                let grouped: Vec<PlanNode> = items.iter().filter_map(Value::as_object).flat_map(mysql_children).collect();
+                children.push(PlanNode {
+                    operation: mysql_operation(name, node),
+                    children: grouped,
+                    ..PlanNode::default()
+                });
+            }
+            _ => {
+                properties.insert(name.clone(), value.clone());
+            }
+        }
+    }
+
+    PlanNode {
+        operation: mysql_operation(key, node),
+        relation: text("table_name"),
+        index: text("key"),
+        detail: text("attached_condition"),
+        startup_cost: None,
+        total_cost: cost("query_cost").or_else(|| cost("prefix_cost")),
+        estimated_rows: mysql_number(node.get("rows_produced_per_join")),
+        actual_rows: None,
+        actual_time_ms: None,
+        loops: None,
+        properties,
+        children,
+    }
+}
+
+// 陣列中的元素只是包裝（例如 {"table": {...}}），取出其中的操作
+fn mysql_children(item: &Map<String, Value>) -> Vec<PlanNode> {
+    let nodes: Vec<PlanNode> = item
+        .iter()
+        .filter_map(|(name, value)| match value {
+            Value::Object(child) if MYSQL_PLAN_OBJECTS.contains(&name.as_str()) => Some(mysql_node(name, child)),
+            _ => None,
+        })
+        .collect();
+    if nodes.is_empty() {
+        vec![mysql_node("", item)]
+    } else {
+        nodes
+    }
+}
+
+async fn sqlite_plan(connection: &DatabaseConnection, sql: &str) -> Result<ExplainedPlan, String> {
+    let pool = connect_sqlite(connection).await?;
+    let result = sqlx::query(&format!("EXPLAIN QUERY PLAN {sql}"))
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("執行 EXPLAIN 錯誤: {e}"));
+    pool.close().await;
+    let rows = result?;
+
+    // 每一行是 (id, parent, notused, detail)，parent 為 0 的是最上層
+    let mut entries = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let id: i64 = row.try_get("id").map_err(|e| format!("讀取計畫錯誤: {e}"))?;
+        let parent: i64 = row.try_get("parent").map_err(|e| format!("讀取計畫錯誤: {e}"))?;
+        let detail: String = row.try_get("detail").map_err(|e| format!("讀取計畫錯誤: {e}"))?;
+        entries.push((id, parent, detail));
+    }
+    let raw = Value::Array(
+        entries
+            .iter()
+            .map(|(id, parent, detail)| serde_json::json!({ "id": id, "parent": parent, "detail": detail }))
+            .collect(),
+    );
+
+    let mut children_of: HashMap<i64, Vec<usize>> = HashMap::new();
+    for (index, (_, parent, _)) in entries.iter().enumerate() {
+        children_of.entry(*parent).or_default().push(index);
+    }
+    let plan = PlanNode {
+        operation: "Query Plan".to_string(),
+        children: sqlite_children(&entries, &children_of, 0),
+        ..PlanNode::default()
+    };
+    Ok(ExplainedPlan {
+        plan,
+        raw,
+        planning_time_ms: None,
+        execution_time_ms: None,
+    })
+}
+
+fn sqlite_children(entries: &[(i64, i64, String)], children_of: &HashMap<i64, Vec<usize>>, parent: i64) -> Vec<PlanNode> {
+    children_of
+        .get(&parent)
+        .map(|indexes| {
+            indexes
+                .iter()
+                .map(|&index| {
+                    let (id, _, detail) = &entries[index];
+                    let mut node = sqlite_node(detail);
+                    // id 一定比 parent 大，不會形成循環
+                    if *id > parent {
+                        node.children = sqlite_children(entries, children_of, *id);
+                    }
+                    node
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// 例如 "SEARCH users USING INDEX idx_email (email=?)" 或 "SCAN orders"
+fn sqlite_node(detail: &str) -> PlanNode {
+    let mut words = detail.split_whitespace();
+    let first = words.next().unwrap_or_default();
+    let (operation, relation) = match first {
+        "SCAN" | "SEARCH" => {
+            let relation = words.next().filter(|word| *word != "TABLE").or_else(|| words.next());
+            (if first == "SCAN" { "Scan" } else { "Search" }.to_string(), relation.map(str::to_string))
+        }
+        _ => (detail.to_string(), None),
+    };
+    let index = ["USING COVERING INDEX ", "USING INDEX ", "USING PRIMARY KEY"]
+        .iter()
+        .find_map(|marker| detail.find(marker).map(|position| (marker, position)))
+        .map(|(marker, position)| match *marker {
+            "USING PRIMARY KEY" => "PRIMARY KEY".to_string(),
+            _ => detail[position + marker.len()..].split_whitespace().next().unwrap_or_default().to_string(),
+        });
+
+    PlanNode {
+        operation,
+        relation,
+        index,
+        detail: Some(detail.to_string()),
+        ..PlanNode::default()
+    }
+}
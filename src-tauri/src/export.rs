@@ -0,0 +1,498 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::binary::{binary_bytes, to_hex};
+use crate::columnar_export::ColumnarWriter;
+use crate::dialect::{quote_identifier, sql_literal};
+use crate::query::{stream_queries, stream_query, ResultColumn, RowHandler};
+use crate::spatial::{geometry_hex, GeometryFormat};
+use crate::sql_guard::{check_read_only, reject_risky};
+use crate::sql_splitter::split_statements;
+use crate::xlsx_export::XlsxWriter;
+use crate::DatabaseConnection;
+
+// 每匯出多少行發送一次進度事件
+const PROGRESS_INTERVAL: u64 = 5000;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportRequest {
+    pub connection: DatabaseConnection,
+    pub sql: String,
+    pub format: String, // 'csv', 'tsv', 'json', 'jsonl', 'sql', 'xlsx', 'parquet', 'arrow'
+    pub file_path: Option<String>, // 未指定時開啟儲存對話框
+    #[serde(default)]
+    pub options: ExportOptions,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub delimiter: Option<String>, // 預設 CSV 為 ','，TSV 為 '\t'
+    pub quote_char: char,
+    pub quoting: String, // 'necessary', 'always', 'never'
+    pub include_header: bool,
+    pub null_marker: String,
+    pub encoding: String, // 'utf-8', 'utf-8-bom', 'utf-16le'
+    pub table_name: String, // INSERT 語句的目標表格
+    pub target_dialect: Option<String>, // INSERT 語句的方言，預設為來源資料庫
+    pub rows_per_insert: usize,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            quote_char: '"',
+            quoting: "necessary".to_string(),
+            include_header: true,
+            null_marker: String::new(),
+            encoding: "utf-8".to_string(),
+            table_name: "exported_data".to_string(),
+            target_dialect: None,
+            rows_per_insert: 100,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportResult {
+    pub success: bool,
+    pub file_path: Option<String>,
+    pub rows_exported: u64,
+    pub execution_time: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportProgress {
+    pub file_path: String,
+    pub rows_exported: u64,
+}
+
+#[tauri::command]
+pub async fn export_query(app: AppHandle, request: ExportRequest) -> Result<ExportResult, String> {
+    let start_time = std::time::Instant::now();
+
+    let format = ExportFormat::parse(&request.format)?;
+    check_read_only(&request.connection, &request.sql)?;
+    reject_risky(&request.connection, &request.sql, "匯出")?;
+
+    let path = match &request.file_path {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => match choose_export_path(&app, format).await? {
+            Some(path) => path,
+            None => {
+                return Ok(ExportResult {
+                    success: false,
+                    file_path: None,
+                    rows_exported: 0,
+                    execution_time: 0,
+                    message: "已取消匯出".to_string(),
+                })
+            }
+        },
+    };
+    let file_path = path.to_string_lossy().into_owned();
+
+    let result = export_to_file(&app, &request, format, &path).await;
+    let execution_time = start_time.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(rows_exported) => Ok(ExportResult {
+            success: true,
+            file_path: Some(file_path.clone()),
+            rows_exported,
+            execution_time,
+            message: format!("匯出成功，共 {rows_exported} 行至 {file_path}"),
+        }),
+        Err(error) => Ok(ExportResult {
+            success: false,
+            file_path: Some(file_path),
+            rows_exported: 0,
+            execution_time,
+            message: format!("匯出失敗: {error}"),
+        }),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ExportFormat {
+    Csv,
+    Tsv,
+    Json,
+    JsonLines,
+    SqlInsert,
+    Xlsx,
+    Parquet,
+    ArrowIpc,
+}
+
+impl ExportFormat {
+    pub(crate) fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "csv" => Ok(Self::Csv),
+            "tsv" => Ok(Self::Tsv),
+            "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::JsonLines),
+            "sql" => Ok(Self::SqlInsert),
+            "xlsx" => Ok(Self::Xlsx),
+            "parquet" => Ok(Self::Parquet),
+            "arrow" => Ok(Self::ArrowIpc),
+            other => Err(format!("不支援的匯出格式: {other}")),
+        }
+    }
+
+    fn filter(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Csv => ("CSV", "csv"),
+            Self::Tsv => ("TSV", "tsv"),
+            Self::Json => ("JSON", "json"),
+            Self::JsonLines => ("JSON Lines", "jsonl"),
+            Self::SqlInsert => ("SQL", "sql"),
+            Self::Xlsx => ("Excel", "xlsx"),
+            Self::Parquet => ("Parquet", "parquet"),
+            Self::ArrowIpc => ("Arrow IPC", "arrow"),
+        }
+    }
+}
+
+// 透過 tauri-plugin-dialog 讓使用者選擇儲存位置
+async fn choose_export_path(app: &AppHandle, format: ExportFormat) -> Result<Option<PathBuf>, String> {
+    let (filter_name, extension) = format.filter();
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+
+    app.dialog()
+        .file()
+        .add_filter(filter_name, &[extension])
+        .set_file_name(format!("export.{extension}"))
+        .save_file(move |path| {
+            let _ = sender.send(path);
+        });
+
+    match receiver.await.map_err(|e| format!("儲存對話框錯誤: {e}"))? {
+        Some(path) => path.into_path().map(Some).map_err(|e| format!("無效的檔案路徑: {e}")),
+        None => Ok(None),
+    }
+}
+
+async fn export_to_file(
+    app: &AppHandle,
+    request: &ExportRequest,
+    format: ExportFormat,
+    path: &PathBuf,
+) -> Result<u64, String> {
+    let file = File::create(path).map_err(|e| format!("無法建立檔案: {e}"))?;
+    let db_type = request.connection.db_type.as_str();
+    let dialect = request.options.target_dialect.clone()
+        .unwrap_or_else(|| db_type.to_string());
+
+    let writer: Box<dyn ExportWriter> = match format {
+        ExportFormat::Csv | ExportFormat::Tsv => {
+            let default_delimiter = if format == ExportFormat::Tsv { "\t" } else { "," };
+            Box::new(DelimitedWriter {
+                output: EncodedWriter::new(BufWriter::new(file), &request.options.encoding)?,
+                delimiter: request.options.delimiter.clone().unwrap_or_else(|| default_delimiter.to_string()),
+                quote_char: request.options.quote_char,
+                quoting: request.options.quoting.clone(),
+                include_header: request.options.include_header,
+                null_marker: request.options.null_marker.clone(),
+            })
+        }
+        ExportFormat::Json | ExportFormat::JsonLines => Box::new(JsonWriter {
+            output: EncodedWriter::new(BufWriter::new(file), &request.options.encoding)?,
+            columns: vec![],
+            first_row: true,
+            lines: format == ExportFormat::JsonLines,
+        }),
+        ExportFormat::SqlInsert => Box::new(InsertWriter {
+            output: EncodedWriter::new(BufWriter::new(file), &request.options.encoding)?,
+            table: quote_identifier(&dialect, &request.options.table_name),
+            dialect,
+            column_list: String::new(),
+            rows_per_insert: request.options.rows_per_insert.max(1),
+            pending_rows: 0,
+        }),
+        ExportFormat::Xlsx => {
+            // rust_xlsxwriter 在儲存時才寫入完整檔案
+            drop(file);
+            Box::new(XlsxWriter::new(path.clone()))
+        }
+        ExportFormat::Parquet => Box::new(ColumnarWriter::parquet(file, db_type)),
+        ExportFormat::ArrowIpc => Box::new(ColumnarWriter::arrow_ipc(file, db_type)),
+    };
+
+    let mut handler = ExportHandler {
+        app: app.clone(),
+        file_path: path.to_string_lossy().into_owned(),
+        writer,
+        rows_exported: 0,
+    };
+
+    // 只有 INSERT 語句需要寫回資料庫時能還原的幾何，其他格式輸出 GeoJSON
+    let geometry = match format {
+        ExportFormat::SqlInsert => GeometryFormat::Native,
+        _ => GeometryFormat::GeoJson,
+    };
+    // Excel 每條語句各自一個工作表，其他格式視為單一查詢
+    let rows_exported = if format == ExportFormat::Xlsx {
+        let statements = split_statements(&request.sql, db_type);
+        stream_queries(&request.connection, &statements, geometry, &mut handler).await?
+    } else {
+        stream_query(&request.connection, &request.sql, geometry, &mut handler).await?
+    };
+    handler.writer.finish().map_err(|e| format!("寫入檔案錯誤: {e}"))?;
+    handler.emit_progress();
+
+    Ok(rows_exported)
+}
+
+struct ExportHandler {
+    app: AppHandle,
+    file_path: String,
+    writer: Box<dyn ExportWriter>,
+    rows_exported: u64,
+}
+
+impl ExportHandler {
+    fn emit_progress(&self) {
+        let _ = self.app.emit("export-progress", ExportProgress {
+            file_path: self.file_path.clone(),
+            rows_exported: self.rows_exported,
+        });
+    }
+}
+
+impl RowHandler for ExportHandler {
+    fn on_columns(&mut self, columns: &[ResultColumn]) -> Result<(), String> {
+        self.writer.begin(columns).map_err(|e| format!("寫入檔案錯誤: {e}"))
+    }
+
+    fn on_row(&mut self, values: Vec<Value>) -> Result<(), String> {
+        self.writer.write_row(&values).map_err(|e| format!("寫入檔案錯誤: {e}"))?;
+        self.rows_exported += 1;
+        if self.rows_exported.is_multiple_of(PROGRESS_INTERVAL) {
+            self.emit_progress();
+        }
+        Ok(())
+    }
+}
+
+pub(crate) trait ExportWriter: Send {
+    fn begin(&mut self, columns: &[ResultColumn]) -> std::io::Result<()>;
+    fn write_row(&mut self, values: &[Value]) -> std::io::Result<()>;
+    fn finish(&mut self) -> std::io::Result<()>;
+}
+
+// 依選擇的編碼輸出文字
+struct EncodedWriter {
+    inner: BufWriter<File>,
+    utf16: bool,
+}
+
+impl EncodedWriter {
+    fn new(mut inner: BufWriter<File>, encoding: &str) -> Result<Self, String> {
+        let utf16 = match encoding.to_lowercase().as_str() {
+            "utf-8" | "utf8" => false,
+            "utf-8-bom" | "utf8-bom" => {
+                inner.write_all(&[0xEF, 0xBB, 0xBF]).map_err(|e| format!("寫入檔案錯誤: {e}"))?;
+                false
+            }
+            "utf-16le" | "utf16le" => {
+                inner.write_all(&[0xFF, 0xFE]).map_err(|e| format!("寫入檔案錯誤: {e}"))?;
+                true
+            }
+            other => return Err(format!("不支援的編碼: {other}")),
+        };
+        Ok(Self { inner, utf16 })
+    }
+
+    fn write_str(&mut self, text: &str) -> std::io::Result<()> {
+        if self.utf16 {
+            for unit in text.encode_utf16() {
+                self.inner.write_all(&unit.to_le_bytes())?;
+            }
+            Ok(())
+        } else {
+            self.inner.write_all(text.as_bytes())
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// 單元格的純文字表示，二進位值輸出為 \x 開頭的十六進位，物件與陣列輸出為 JSON
+// 幾何輸出為 PostGIS 文字格式的十六進位 (E)WKB
+pub(crate) fn value_text(value: &Value) -> String {
+    if let Some((hex, _)) = geometry_hex(value) {
+        return hex.to_string();
+    }
+    if let Some(bytes) = binary_bytes(value) {
+        return format!("\\x{}", to_hex(&bytes));
+    }
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+struct DelimitedWriter {
+    output: EncodedWriter,
+    delimiter: String,
+    quote_char: char,
+    quoting: String,
+    include_header: bool,
+    null_marker: String,
+}
+
+impl DelimitedWriter {
+    fn field(&self, text: &str, is_null: bool) -> String {
+        if is_null {
+            return self.null_marker.clone();
+        }
+
+        let needs_quotes = match self.quoting.as_str() {
+            "always" => true,
+            "never" => false,
+            // 空字串與空的 NULL 標記相同時也加上引號以便區分
+            _ => text.contains(self.delimiter.as_str())
+                || text.contains(self.quote_char)
+                || text.contains('\n')
+                || text.contains('\r')
+                || (text.is_empty() && self.null_marker.is_empty()),
+        };
+
+        if needs_quotes {
+            let quote = self.quote_char.to_string();
+            let escaped = text.replace(&quote, &format!("{quote}{quote}"));
+            format!("{quote}{escaped}{quote}")
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn write_line(&mut self, fields: Vec<String>) -> std::io::Result<()> {
+        let line = fields.join(&self.delimiter);
+        self.output.write_str(&line)?;
+        self.output.write_str("\r\n")
+    }
+}
+
+impl ExportWriter for DelimitedWriter {
+    fn begin(&mut self, columns: &[ResultColumn]) -> std::io::Result<()> {
+        if !self.include_header {
+            return Ok(());
+        }
+        let fields = columns.iter().map(|column| self.field(&column.name, false)).collect();
+        self.write_line(fields)
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> std::io::Result<()> {
+        let fields = values.iter().map(|value| self.field(&value_text(value), value.is_null())).collect();
+        self.write_line(fields)
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        self.output.flush()
+    }
+}
+
+// JSON 陣列或 JSON Lines（每行一個物件）
+struct JsonWriter {
+    output: EncodedWriter,
+    columns: Vec<String>,
+    first_row: bool,
+    lines: bool,
+}
+
+impl ExportWriter for JsonWriter {
+    fn begin(&mut self, columns: &[ResultColumn]) -> std::io::Result<()> {
+        self.columns = columns.iter().map(|column| column.name.clone()).collect();
+        if !self.lines {
+            self.output.write_str("[")?;
+        }
+        Ok(())
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> std::io::Result<()> {
+        let object: serde_json::Map<String, Value> = self.columns.iter().cloned().zip(values.iter().cloned()).collect();
+        let json = Value::Object(object).to_string();
+
+        if self.lines {
+            self.output.write_str(&json)?;
+            return self.output.write_str("\n");
+        }
+
+        let separator = if self.first_row { "\n  " } else { ",\n  " };
+        self.first_row = false;
+        self.output.write_str(separator)?;
+        self.output.write_str(&json)
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        if !self.lines {
+            self.output.write_str(if self.first_row { "]\n" } else { "\n]\n" })?;
+        }
+        self.output.flush()
+    }
+}
+
+// 多行 VALUES 的 INSERT 語句
+struct InsertWriter {
+    output: EncodedWriter,
+    dialect: String,
+    table: String,
+    column_list: String,
+    rows_per_insert: usize,
+    pending_rows: usize,
+}
+
+impl ExportWriter for InsertWriter {
+    fn begin(&mut self, columns: &[ResultColumn]) -> std::io::Result<()> {
+        self.column_list = columns
+            .iter()
+            .map(|column| quote_identifier(&self.dialect, &column.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(())
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> std::io::Result<()> {
+        let tuple = values
+            .iter()
+            .map(|value| sql_literal(&self.dialect, value))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if self.pending_rows == 0 {
+            let header = format!("INSERT INTO {} ({}) VALUES\n", self.table, self.column_list);
+            self.output.write_str(&header)?;
+        } else {
+            self.output.write_str(",\n")?;
+        }
+        self.output.write_str(&format!("({tuple})"))?;
+
+        self.pending_rows += 1;
+        if self.pending_rows >= self.rows_per_insert {
+            self.output.write_str(";\n")?;
+            self.pending_rows = 0;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        if self.pending_rows > 0 {
+            self.output.write_str(";\n")?;
+            self.pending_rows = 0;
+        }
+        self.output.flush()
+    }
+}
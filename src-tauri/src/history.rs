@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use tauri::{AppHandle, State};
+
+use crate::app_db::{like_pattern, now_millis, AppDatabase};
+use crate::sql_guard::PendingConfirmations;
+use crate::{DatabaseConnection, QueryRequest, QueryResult};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryHistoryEntry {
+    pub id: i64,
+    pub connection_id: String,
+    pub connection_name: String,
+    pub db_type: String,
+    pub database: String,
+    pub sql: String,
+    pub executed_at: i64, // Unix 時間（毫秒）
+    pub duration_ms: u64,
+    pub row_count: Option<u64>, // SELECT 為返回的行數，其他語句為影響的行數
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchHistoryRequest {
+    pub text: Option<String>, // 在 SQL 中搜尋的文字
+    pub connection_id: Option<String>,
+    pub from: Option<i64>, // Unix 時間（毫秒），包含
+    pub to: Option<i64>, // Unix 時間（毫秒），不包含
+    pub success: Option<bool>,
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl Default for SearchHistoryRequest {
+    fn default() -> Self {
+        Self {
+            text: None,
+            connection_id: None,
+            from: None,
+            to: None,
+            success: None,
+            limit: 100,
+            offset: 0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchHistoryResult {
+    pub success: bool,
+    pub entries: Vec<QueryHistoryEntry>,
+    pub total: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RerunHistoryRequest {
+    pub id: i64,
+    pub connection: DatabaseConnection, // 歷史記錄不保存密碼，重新執行時需要完整的連接設定
+    #[serde(default)]
+    pub confirmation_token: Option<String>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub max_rows: Option<usize>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PurgeHistoryRequest {
+    pub before: Option<i64>, // 刪除此時間（毫秒）之前的記錄
+    pub older_than_days: Option<u32>,
+    pub connection_id: Option<String>, // 只刪除指定連接的記錄
+    pub ids: Vec<i64>, // 刪除指定的記錄
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgeHistoryResult {
+    pub success: bool,
+    pub deleted: u64,
+    pub message: String,
+}
+
+// 記錄失敗不影響查詢結果
+pub(crate) async fn record_query(
+    app: &AppHandle,
+    app_db: &AppDatabase,
+    connection: &DatabaseConnection,
+    sql: &str,
+    result: &QueryResult,
+) {
+    let Ok(pool) = app_db.pool(app).await else {
+        return;
+    };
+    let error = (!result.success).then(|| result.message.clone());
+    let _ = sqlx::query(
+        "INSERT INTO query_history (connection_id, connection_name, db_type, database_name, sql, executed_at, duration_ms, row_count, success, error)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(connection_id(connection))
+    .bind(&connection.name)
+    .bind(&connection.db_type)
+    .bind(&connection.database)
+    .bind(sql)
+    .bind(now_millis())
+    .bind(result.execution_time as i64)
+    .bind(result.affected_rows.map(|rows| rows as i64))
+    .bind(result.success)
+    .bind(error)
+    .execute(pool)
+    .await;
+}
+
+// 前端沒有提供識別碼時以連接名稱區分
+fn connection_id(connection: &DatabaseConnection) -> &str {
+    if connection.id.is_empty() {
+        &connection.name
+    } else {
+        &connection.id
+    }
+}
+
+fn history_entry(row: &sqlx::sqlite::SqliteRow) -> Result<QueryHistoryEntry, sqlx::Error> {
+    Ok(QueryHistoryEntry {
+        id: row.try_get("id")?,
+        connection_id: row.try_get("connection_id")?,
+        connection_name: row.try_get("connection_name")?,
+        db_type: row.try_get("db_type")?,
+        database: row.try_get("database_name")?,
+        sql: row.try_get("sql")?,
+        executed_at: row.try_get("executed_at")?,
+        duration_ms: row.try_get::<i64, _>("duration_ms")?.max(0) as u64,
+        row_count: row.try_get::<Option<i64>, _>("row_count")?.map(|rows| rows.max(0) as u64),
+        success: row.try_get("success")?,
+        error: row.try_get("error")?,
+    })
+}
+
+// 所有條件都是選用的，未指定的參數為 NULL 時不過濾
+const SEARCH_FILTER: &str = "
+    WHERE (?1 IS NULL OR sql LIKE ?1 ESCAPE '\\')
+      AND (?2 IS NULL OR connection_id = ?2)
+      AND (?3 IS NULL OR executed_at >= ?3)
+      AND (?4 IS NULL OR executed_at < ?4)
+      AND (?5 IS NULL OR success = ?5)";
+
+#[tauri::command]
+pub async fn search_query_history(
+    app: AppHandle,
+    app_db: State<'_, AppDatabase>,
+    request: SearchHistoryRequest,
+) -> Result<SearchHistoryResult, String> {
+    let pool = app_db.pool(&app).await?;
+    let pattern = request.text.as_deref().filter(|text| !text.is_empty()).map(like_pattern);
+
+    let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM query_history {SEARCH_FILTER}"))
+        .bind(&pattern)
+        .bind(&request.connection_id)
+        .bind(request.from)
+        .bind(request.to)
+        .bind(request.success)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("搜尋查詢歷史錯誤: {e}"))?;
+
+    let rows = sqlx::query(&format!(
+        "SELECT * FROM query_history {SEARCH_FILTER} ORDER BY executed_at DESC, id DESC LIMIT ?6 OFFSET ?7"
+    ))
+    .bind(&pattern)
+    .bind(&request.connection_id)
+    .bind(request.from)
+    .bind(request.to)
+    .bind(request.success)
+    .bind(request.limit as i64)
+    .bind(request.offset as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("搜尋查詢歷史錯誤: {e}"))?;
+
+    let entries = rows
+        .iter()
+        .map(history_entry)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("讀取查詢歷史錯誤: {e}"))?;
+
+    let count = entries.len();
+    Ok(SearchHistoryResult {
+        success: true,
+        entries,
+        total: total.max(0) as u64,
+        message: format!("找到 {total} 筆記錄，返回 {count} 筆"),
+    })
+}
+
+// 以目前的連接設定重新執行歷史記錄中的 SQL，結果同樣會記錄到歷史
+#[tauri::command]
+pub async fn rerun_query_history(
+    app: AppHandle,
+    app_db: State<'_, AppDatabase>,
+    confirmations: State<'_, PendingConfirmations>,
+    request: RerunHistoryRequest,
+) -> Result<QueryResult, String> {
+    let pool = app_db.pool(&app).await?;
+    let sql: Option<String> = sqlx::query_scalar("SELECT sql FROM query_history WHERE id = ?")
+        .bind(request.id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("讀取查詢歷史錯誤: {e}"))?;
+    let Some(sql) = sql else {
+        let id = request.id;
+        return Err(format!("找不到查詢歷史記錄: {id}"));
+    };
+
+    let query = QueryRequest {
+        connection: request.connection,
+        sql,
+        confirmation_token: request.confirmation_token,
+        timeout_ms: request.timeout_ms,
+        max_rows: request.max_rows,
+        row_offset: 0,
+    };
+    crate::execute_query(app.clone(), app_db, confirmations, query).await
+}
+
+#[tauri::command]
+pub async fn purge_query_history(
+    app: AppHandle,
+    app_db: State<'_, AppDatabase>,
+    request: PurgeHistoryRequest,
+) -> Result<PurgeHistoryResult, String> {
+    let days_ago = request
+        .older_than_days
+        .map(|days| now_millis() - i64::from(days) * 24 * 60 * 60 * 1000);
+    let before = match (request.before, days_ago) {
+        (Some(before), Some(days_ago)) => Some(before.min(days_ago)),
+        (before, days_ago) => before.or(days_ago),
+    };
+    if before.is_none() && request.connection_id.is_none() && request.ids.is_empty() {
+        return Err("請指定要刪除的記錄範圍".to_string());
+    }
+
+    let pool = app_db.pool(&app).await?;
+    let ids = serde_json::to_string(&request.ids).map_err(|e| format!("序列化錯誤: {e}"))?;
+    let result = sqlx::query(
+        "DELETE FROM query_history
+         WHERE (?1 IS NULL OR executed_at < ?1)
+           AND (?2 IS NULL OR connection_id = ?2)
+           AND (?3 = '[]' OR id IN (SELECT value FROM json_each(?3)))",
+    )
+    .bind(before)
+    .bind(&request.connection_id)
+    .bind(ids)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("刪除查詢歷史錯誤: {e}"))?;
+
+    let deleted = result.rows_affected();
+    Ok(PurgeHistoryResult {
+        success: true,
+        deleted,
+        message: format!("已刪除 {deleted} 筆查詢歷史"),
+    })
+}
@@ -0,0 +1,863 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::types::chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::connection::DbPool;
+use crate::dialect::{quote_identifier, sql_literal};
+use crate::query::{stream_query, CollectedRows};
+use crate::spatial::GeometryFormat;
+use crate::sql_guard::ensure_writable;
+use crate::DatabaseConnection;
+
+// 單條 INSERT 最多包含的行數，避免超過 MySQL max_allowed_packet
+const ROWS_PER_INSERT: usize = 500;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportRequest {
+    pub connection: DatabaseConnection,
+    pub file_path: Option<String>, // 未指定時開啟選擇檔案對話框
+    pub format: String, // 'csv', 'tsv', 'jsonl'
+    pub table_name: String,
+    pub schema: Option<String>,
+    pub mode: String, // 'create' 建立新表格，'append' 寫入現有表格
+    #[serde(default)]
+    pub options: ImportOptions,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportOptions {
+    pub delimiter: Option<String>, // 預設 CSV 為 ','，TSV 為 '\t'
+    pub quote_char: char,
+    pub has_header: bool,
+    pub null_marker: String,
+    pub sample_rows: usize, // 推斷欄位型別時讀取的行數
+    pub batch_size: usize, // 每個交易寫入的行數
+    pub error_policy: String, // 'abort', 'skip', 'log'
+    pub column_mapping: Option<HashMap<String, String>>, // 檔案欄位 -> 表格欄位
+    pub max_reported_errors: usize,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            quote_char: '"',
+            has_header: true,
+            null_marker: String::new(),
+            sample_rows: 1000,
+            batch_size: 1000,
+            error_policy: "abort".to_string(),
+            column_mapping: None,
+            max_reported_errors: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportColumn {
+    pub source: String,
+    pub target: String,
+    pub inferred_type: String,
+    pub sql_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BadRow {
+    pub line: u64,
+    pub error: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub success: bool,
+    pub table_name: String,
+    pub columns: Vec<ImportColumn>,
+    pub rows_imported: u64,
+    pub rows_failed: u64,
+    pub bad_rows: Vec<BadRow>,
+    pub error_log_path: Option<String>,
+    pub execution_time: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportProgress {
+    pub file_path: String,
+    pub rows_imported: u64,
+    pub rows_failed: u64,
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+}
+
+#[tauri::command]
+pub async fn import_file(app: AppHandle, request: ImportRequest) -> Result<ImportResult, String> {
+    let start_time = std::time::Instant::now();
+    ensure_writable(&request.connection, "匯入資料")?;
+
+    let path = match &request.file_path {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => match choose_import_path(&app).await? {
+            Some(path) => path,
+            None => return Ok(ImportResult::cancelled(&request.table_name)),
+        },
+    };
+
+    let mut report = ImportReport::default();
+    let result = import_into_table(&app, &request, &path, &mut report).await;
+    let execution_time = start_time.elapsed().as_millis() as u64;
+
+    let (success, message) = match result {
+        Ok(()) if report.aborted.is_none() => {
+            let (imported, failed) = (report.rows_imported, report.rows_failed);
+            (true, format!("匯入完成，成功 {imported} 行，失敗 {failed} 行"))
+        }
+        Ok(()) => {
+            let imported = report.rows_imported;
+            let reason = report.aborted.clone().unwrap_or_default();
+            (false, format!("匯入中止: {reason}（已提交 {imported} 行）"))
+        }
+        Err(error) => (false, format!("匯入失敗: {error}")),
+    };
+
+    Ok(ImportResult {
+        success,
+        table_name: request.table_name.clone(),
+        columns: report.columns,
+        rows_imported: report.rows_imported,
+        rows_failed: report.rows_failed,
+        bad_rows: report.bad_rows,
+        error_log_path: report.error_log_path,
+        execution_time,
+        message,
+    })
+}
+
+impl ImportResult {
+    fn cancelled(table_name: &str) -> Self {
+        Self {
+            success: false,
+            table_name: table_name.to_string(),
+            columns: vec![],
+            rows_imported: 0,
+            rows_failed: 0,
+            bad_rows: vec![],
+            error_log_path: None,
+            execution_time: 0,
+            message: "已取消匯入".to_string(),
+        }
+    }
+}
+
+async fn choose_import_path(app: &AppHandle) -> Result<Option<PathBuf>, String> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+
+    app.dialog()
+        .file()
+        .add_filter("資料檔案", &["csv", "tsv", "txt", "jsonl", "ndjson"])
+        .pick_file(move |path| {
+            let _ = sender.send(path);
+        });
+
+    match receiver.await.map_err(|e| format!("選擇檔案對話框錯誤: {e}"))? {
+        Some(path) => path.into_path().map(Some).map_err(|e| format!("無效的檔案路徑: {e}")),
+        None => Ok(None),
+    }
+}
+
+#[derive(Debug, Default)]
+struct ImportReport {
+    columns: Vec<ImportColumn>,
+    rows_imported: u64,
+    rows_failed: u64,
+    bad_rows: Vec<BadRow>,
+    error_log_path: Option<String>,
+    aborted: Option<String>,
+}
+
+// 欄位的邏輯型別，用於推斷與轉換檔案中的值
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImportKind {
+    Integer,
+    Float,
+    Boolean,
+    Date,
+    DateTime,
+    DateTimeTz, // 會依工作階段時區轉換的欄位，只對應現有表格的欄位
+    Json,
+    Text,
+}
+
+impl ImportKind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Boolean => "boolean",
+            Self::Date => "date",
+            Self::DateTime | Self::DateTimeTz => "datetime",
+            Self::Json => "json",
+            Self::Text => "text",
+        }
+    }
+
+    fn sql_type(self, db_type: &str) -> &'static str {
+        match (self, db_type) {
+            (Self::Integer, "sqlite") => "INTEGER",
+            (Self::Integer, _) => "BIGINT",
+            (Self::Float, "sqlite") => "REAL",
+            (Self::Float, "mysql") => "DOUBLE",
+            (Self::Float, _) => "DOUBLE PRECISION",
+            (Self::Boolean, "sqlite") => "INTEGER",
+            (Self::Boolean, _) => "BOOLEAN",
+            (Self::Date, "sqlite") => "TEXT",
+            (Self::Date, _) => "DATE",
+            (Self::DateTime, "sqlite") => "TEXT",
+            (Self::DateTime, "mysql") => "DATETIME(6)",
+            (Self::DateTime, _) => "TIMESTAMP",
+            (Self::DateTimeTz, "sqlite") => "TEXT",
+            (Self::DateTimeTz, "mysql") => "TIMESTAMP(6)",
+            (Self::DateTimeTz, _) => "TIMESTAMPTZ",
+            (Self::Json, "mysql") => "JSON",
+            (Self::Json, "postgresql") => "JSONB",
+            (Self::Json, _) => "TEXT",
+            (Self::Text, _) => "TEXT",
+        }
+    }
+
+    // 兩種型別合併為能同時容納兩者的型別
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Integer, Self::Float) | (Self::Float, Self::Integer) => Self::Float,
+            (Self::Date, Self::DateTime) | (Self::DateTime, Self::Date) => Self::DateTime,
+            _ => Self::Text,
+        }
+    }
+
+    // 現有表格欄位的型別名稱；MySQL 的 TIMESTAMP 與 PostgreSQL 的 TIMESTAMPTZ 帶時區語意
+    fn from_type_name(type_name: &str, db_type: &str) -> Self {
+        let upper = type_name.to_uppercase();
+        match upper.as_str() {
+            "BOOL" | "BOOLEAN" => Self::Boolean,
+            "DATE" => Self::Date,
+            "TIMESTAMPTZ" => Self::DateTimeTz,
+            "TIMESTAMP" if db_type == "mysql" => Self::DateTimeTz,
+            "DATETIME" | "TIMESTAMP" => Self::DateTime,
+            "JSON" | "JSONB" => Self::Json,
+            "FLOAT" | "FLOAT4" | "FLOAT8" | "DOUBLE" | "REAL" => Self::Float,
+            _ if upper.contains("INT") || upper == "YEAR" => Self::Integer,
+            _ => Self::Text,
+        }
+    }
+}
+
+fn parse_offset_datetime(text: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(text)
+        .or_else(|_| DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f%:z"))
+        .ok()
+}
+
+// 帶時區偏移的值轉為 UTC，用於沒有時區的欄位
+fn parse_datetime(text: &str) -> Option<NaiveDateTime> {
+    if let Some(datetime) = parse_offset_datetime(text) {
+        return Some(datetime.naive_utc());
+    }
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M", "%Y/%m/%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    ["%Y-%m-%d", "%Y/%m/%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.trim().to_lowercase().as_str() {
+        "true" | "t" | "yes" | "y" => Some(true),
+        "false" | "f" | "no" | "n" => Some(false),
+        _ => None,
+    }
+}
+
+// 推斷單一值的型別
+fn value_kind(value: &Value) -> Option<ImportKind> {
+    match value {
+        Value::Null => None,
+        Value::Bool(_) => Some(ImportKind::Boolean),
+        Value::Number(n) if n.is_i64() || n.is_u64() => Some(ImportKind::Integer),
+        Value::Number(_) => Some(ImportKind::Float),
+        Value::Array(_) | Value::Object(_) => Some(ImportKind::Json),
+        Value::String(s) => {
+            let text = s.trim();
+            // 有前導零的數字（例如郵遞區號）保留為文字
+            let leading_zero = text.len() > 1 && text.starts_with('0') && !text.starts_with("0.");
+            if !leading_zero && text.parse::<i64>().is_ok() {
+                Some(ImportKind::Integer)
+            } else if !leading_zero && text.parse::<f64>().is_ok_and(|n| n.is_finite()) {
+                Some(ImportKind::Float)
+            } else if parse_bool(text).is_some() {
+                Some(ImportKind::Boolean)
+            } else if parse_date(text).is_some() {
+                Some(ImportKind::Date)
+            } else if parse_datetime(text).is_some() {
+                Some(ImportKind::DateTime)
+            } else {
+                Some(ImportKind::Text)
+            }
+        }
+    }
+}
+
+fn infer_kind<'a>(values: impl Iterator<Item = &'a Value>) -> ImportKind {
+    values
+        .filter_map(value_kind)
+        .reduce(ImportKind::merge)
+        .unwrap_or(ImportKind::Text)
+}
+
+// 依目標型別轉換檔案中的值
+fn convert_value(value: &Value, kind: ImportKind) -> Result<Value, String> {
+    if value.is_null() {
+        return Ok(Value::Null);
+    }
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        other => other.to_string(),
+    };
+
+    match kind {
+        ImportKind::Integer => match value {
+            Value::Number(n) if n.is_i64() || n.is_u64() => Ok(value.clone()),
+            Value::Bool(b) => Ok(Value::Number((*b as i64).into())),
+            _ => text.parse::<i64>()
+                .map(|n| Value::Number(n.into()))
+                .map_err(|_| format!("無法轉換為整數: {text}")),
+        },
+        ImportKind::Float => text.parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| format!("無法轉換為數字: {text}")),
+        ImportKind::Boolean => match value {
+            Value::Bool(b) => Ok(Value::Bool(*b)),
+            _ => match text.as_str() {
+                "1" => Ok(Value::Bool(true)),
+                "0" => Ok(Value::Bool(false)),
+                _ => parse_bool(&text).map(Value::Bool).ok_or_else(|| format!("無法轉換為布林值: {text}")),
+            },
+        },
+        ImportKind::Date => parse_date(&text)
+            .map(|date| Value::String(date.format("%Y-%m-%d").to_string()))
+            .ok_or_else(|| format!("無法轉換為日期: {text}")),
+        ImportKind::DateTime => parse_datetime(&text)
+            .or_else(|| parse_date(&text).and_then(|date| date.and_hms_opt(0, 0, 0)))
+            .map(|datetime| Value::String(datetime.format("%Y-%m-%d %H:%M:%S%.f").to_string()))
+            .ok_or_else(|| format!("無法轉換為日期時間: {text}")),
+        // 保留原本的偏移，否則伺服器會以工作階段時區解讀；沒有偏移的值照原樣交給伺服器
+        ImportKind::DateTimeTz => match parse_offset_datetime(&text) {
+            Some(datetime) => Ok(Value::String(datetime.format("%Y-%m-%d %H:%M:%S%.f%:z").to_string())),
+            None => convert_value(value, ImportKind::DateTime),
+        },
+        ImportKind::Json => match value {
+            Value::String(s) => serde_json::from_str::<Value>(s)
+                .map(|json| Value::String(json.to_string()))
+                .map_err(|_| format!("無效的 JSON: {text}")),
+            other => Ok(Value::String(other.to_string())),
+        },
+        ImportKind::Text => match value {
+            Value::String(_) => Ok(value.clone()),
+            other => Ok(Value::String(other.to_string())),
+        },
+    }
+}
+
+struct SourceRecord {
+    line: u64,
+    values: Vec<Value>,
+    content: String,
+}
+
+// 逐筆讀取 CSV/TSV 或 JSON Lines 檔案
+enum RecordReader {
+    Delimited {
+        reader: csv::Reader<File>,
+        null_marker: String,
+    },
+    JsonLines {
+        lines: std::io::Lines<BufReader<File>>,
+        columns: Vec<String>,
+        line: u64,
+        bytes_read: u64,
+    },
+}
+
+impl RecordReader {
+    fn open(path: &PathBuf, request: &ImportRequest) -> Result<(Self, Vec<String>), String> {
+        let file = File::open(path).map_err(|e| format!("無法開啟檔案: {e}"))?;
+        let options = &request.options;
+
+        match request.format.as_str() {
+            "csv" | "tsv" => {
+                let default_delimiter = if request.format == "tsv" { "\t" } else { "," };
+                let delimiter = options.delimiter.as_deref().unwrap_or(default_delimiter);
+                let delimiter = match delimiter.as_bytes() {
+                    [byte] => *byte,
+                    _ => return Err(format!("分隔符號必須是單一字元: {delimiter}")),
+                };
+                if !options.quote_char.is_ascii() {
+                    let quote_char = options.quote_char;
+                    return Err(format!("引號字元必須是 ASCII 字元: {quote_char}"));
+                }
+                let mut reader = csv::ReaderBuilder::new()
+                    .delimiter(delimiter)
+                    .quote(options.quote_char as u8)
+                    .has_headers(options.has_header)
+                    .flexible(true)
+                    .from_reader(file);
+
+                let headers = if options.has_header {
+                    reader.headers()
+                        .map_err(|e| format!("讀取標題列錯誤: {e}"))?
+                        .iter()
+                        .map(|header| header.trim().to_string())
+                        .collect()
+                } else {
+                    vec![]
+                };
+
+                Ok((Self::Delimited { reader, null_marker: options.null_marker.clone() }, headers))
+            }
+            "jsonl" | "ndjson" => Ok((
+                Self::JsonLines {
+                    lines: BufReader::new(file).lines(),
+                    columns: vec![],
+                    line: 0,
+                    bytes_read: 0,
+                },
+                vec![],
+            )),
+            other => Err(format!("不支援的匯入格式: {other}")),
+        }
+    }
+
+    fn bytes_read(&self) -> u64 {
+        match self {
+            Self::Delimited { reader, .. } => reader.position().byte(),
+            Self::JsonLines { bytes_read, .. } => *bytes_read,
+        }
+    }
+
+    // JSON Lines 的欄位由取樣資料決定
+    fn set_columns(&mut self, names: &[String]) {
+        if let Self::JsonLines { columns, .. } = self {
+            *columns = names.to_vec();
+        }
+    }
+
+    fn next_record(&mut self) -> Option<Result<SourceRecord, BadRow>> {
+        match self {
+            Self::Delimited { reader, null_marker } => {
+                let mut record = csv::StringRecord::new();
+                match reader.read_record(&mut record) {
+                    Ok(false) => None,
+                    Ok(true) => {
+                        let line = record.position().map(|position| position.line()).unwrap_or(0);
+                        let values = record
+                            .iter()
+                            .map(|field| {
+                                if field == null_marker.as_str() {
+                                    Value::Null
+                                } else {
+                                    Value::String(field.to_string())
+                                }
+                            })
+                            .collect();
+                        let content = record.iter().collect::<Vec<_>>().join(",");
+                        Some(Ok(SourceRecord { line, values, content }))
+                    }
+                    Err(e) => {
+                        let line = e.position().map(|position| position.line()).unwrap_or(0);
+                        Some(Err(BadRow { line, error: format!("解析錯誤: {e}"), content: String::new() }))
+                    }
+                }
+            }
+            Self::JsonLines { lines, columns, line, bytes_read } => loop {
+                let text = match lines.next()? {
+                    Ok(text) => text,
+                    Err(e) => return Some(Err(BadRow { line: *line + 1, error: format!("讀取錯誤: {e}"), content: String::new() })),
+                };
+                *line += 1;
+                *bytes_read += text.len() as u64 + 1;
+
+                let trimmed = text.trim().trim_start_matches('\u{feff}');
+                if trimmed.is_empty() {
+                    continue;
+                }
+
+                let object = match serde_json::from_str::<Value>(trimmed) {
+                    Ok(Value::Object(object)) => object,
+                    Ok(_) => return Some(Err(BadRow { line: *line, error: "每行必須是 JSON 物件".to_string(), content: text })),
+                    Err(e) => return Some(Err(BadRow { line: *line, error: format!("JSON 解析錯誤: {e}"), content: text })),
+                };
+
+                // 取樣階段尚未決定欄位時保留原始物件順序
+                let values = if columns.is_empty() {
+                    vec![Value::Object(object)]
+                } else {
+                    columns.iter().map(|column| object.get(column).cloned().unwrap_or(Value::Null)).collect()
+                };
+                return Some(Ok(SourceRecord { line: *line, values, content: text }));
+            },
+        }
+    }
+}
+
+// 為新表格產生不重複的欄位名稱
+fn unique_column_names(headers: &[String], count: usize) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(count);
+    for index in 0..count {
+        let base = headers.get(index)
+            .map(|header| header.trim().to_string())
+            .filter(|header| !header.is_empty())
+            .unwrap_or_else(|| format!("column_{}", index + 1));
+
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while names.iter().any(|existing| existing.eq_ignore_ascii_case(&name)) {
+            name = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+fn qualified_table_name(request: &ImportRequest) -> String {
+    let db_type = request.connection.db_type.as_str();
+    let table = quote_identifier(db_type, &request.table_name);
+    match &request.schema {
+        Some(schema) if !schema.is_empty() => format!("{}.{table}", quote_identifier(db_type, schema)),
+        _ => table,
+    }
+}
+
+// 將檔案欄位對應到現有表格的欄位，返回（檔案欄位索引, 表格欄位名稱, 型別）
+async fn map_existing_columns(
+    request: &ImportRequest,
+    source_names: &[String],
+) -> Result<Vec<(usize, String, ImportKind, String)>, String> {
+    let table = qualified_table_name(request);
+    let mut collected = CollectedRows::default();
+    stream_query(&request.connection, &format!("SELECT * FROM {table} WHERE 1 = 0"), GeometryFormat::GeoJson, &mut collected).await?;
+
+    if collected.columns.is_empty() {
+        return Err(format!("無法取得表格 {table} 的欄位資訊"));
+    }
+
+    let mut mapped = Vec::new();
+    for (index, source) in source_names.iter().enumerate() {
+        let target_name = match &request.options.column_mapping {
+            Some(mapping) => match mapping.get(source) {
+                Some(target) if !target.is_empty() => target.clone(),
+                _ => continue,
+            },
+            None => source.clone(),
+        };
+
+        match collected.columns.iter().find(|column| column.name.eq_ignore_ascii_case(&target_name)) {
+            Some(column) => mapped.push((
+                index,
+                column.name.clone(),
+                ImportKind::from_type_name(&column.type_name, &request.connection.db_type),
+                column.type_name.clone(),
+            )),
+            None if request.options.column_mapping.is_some() => {
+                return Err(format!("表格 {table} 沒有欄位 {target_name}"));
+            }
+            None => {}
+        }
+    }
+
+    if mapped.is_empty() {
+        return Err(format!("檔案欄位與表格 {table} 沒有任何相符的欄位"));
+    }
+    Ok(mapped)
+}
+
+struct BatchContext<'a> {
+    app: &'a AppHandle,
+    pool: &'a DbPool,
+    file_path: String,
+    total_bytes: u64,
+    db_type: String,
+    insert_prefix: String,
+    mapping: Vec<(usize, ImportKind)>,
+    policy: String,
+    max_reported_errors: usize,
+    error_log: Option<BufWriter<File>>,
+}
+
+impl BatchContext<'_> {
+    fn record_bad_row(&mut self, report: &mut ImportReport, bad_row: BadRow) {
+        report.rows_failed += 1;
+        if let Some(log) = &mut self.error_log {
+            let _ = writeln!(log, "第 {} 行: {}\t{}", bad_row.line, bad_row.error, bad_row.content);
+        }
+        if report.bad_rows.len() < self.max_reported_errors {
+            report.bad_rows.push(bad_row);
+        }
+        if self.policy == "abort" && report.aborted.is_none() {
+            let last = report.bad_rows.last().map(|row| format!("第 {} 行 {}", row.line, row.error));
+            report.aborted = last.or_else(|| Some("資料錯誤".to_string()));
+        }
+    }
+
+    fn row_literals(&self, record: &SourceRecord) -> Result<String, String> {
+        let mut literals = Vec::with_capacity(self.mapping.len());
+        for (index, kind) in &self.mapping {
+            let value = record.values.get(*index).unwrap_or(&Value::Null);
+            let converted = convert_value(value, *kind)?;
+            literals.push(sql_literal(&self.db_type, &converted));
+        }
+        Ok(format!("({})", literals.join(", ")))
+    }
+
+    fn insert_statements(&self, tuples: &[String]) -> Vec<String> {
+        tuples
+            .chunks(ROWS_PER_INSERT)
+            .map(|chunk| format!("{}{}", self.insert_prefix, chunk.join(",\n")))
+            .collect()
+    }
+
+    // 以單一交易寫入一批資料；失敗時若策略允許則逐行重試以找出錯誤行
+    async fn flush(&mut self, batch: &mut Vec<SourceRecord>, report: &mut ImportReport, bytes_read: u64) {
+        if batch.is_empty() {
+            return;
+        }
+
+        let mut tuples = Vec::with_capacity(batch.len());
+        let mut valid = Vec::with_capacity(batch.len());
+        for record in batch.drain(..) {
+            match self.row_literals(&record) {
+                Ok(tuple) => {
+                    tuples.push(tuple);
+                    valid.push(record);
+                }
+                Err(error) => {
+                    self.record_bad_row(report, BadRow { line: record.line, error, content: record.content });
+                    if report.aborted.is_some() {
+                        return;
+                    }
+                }
+            }
+        }
+
+        if !tuples.is_empty() {
+            match self.pool.execute_transaction(&self.insert_statements(&tuples)).await {
+                Ok(_) => report.rows_imported += tuples.len() as u64,
+                Err(error) if self.policy == "abort" => {
+                    let line = valid.first().map(|record| record.line).unwrap_or(0);
+                    self.record_bad_row(report, BadRow { line, error, content: String::new() });
+                }
+                Err(_) => {
+                    for (tuple, record) in tuples.iter().zip(valid) {
+                        match self.pool.execute_transaction(&self.insert_statements(std::slice::from_ref(tuple))).await {
+                            Ok(_) => report.rows_imported += 1,
+                            Err(error) => {
+                                self.record_bad_row(report, BadRow { line: record.line, error, content: record.content });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let _ = self.app.emit("import-progress", ImportProgress {
+            file_path: self.file_path.clone(),
+            rows_imported: report.rows_imported,
+            rows_failed: report.rows_failed,
+            bytes_read,
+            total_bytes: self.total_bytes,
+        });
+    }
+}
+
+async fn import_into_table(
+    app: &AppHandle,
+    request: &ImportRequest,
+    path: &PathBuf,
+    report: &mut ImportReport,
+) -> Result<(), String> {
+    let db_type = request.connection.db_type.as_str();
+    let options = &request.options;
+    if !["abort", "skip", "log"].contains(&options.error_policy.as_str()) {
+        let policy = &options.error_policy;
+        return Err(format!("不支援的錯誤處理策略: {policy}"));
+    }
+
+    let (mut reader, headers) = RecordReader::open(path, request)?;
+
+    // 先讀取樣本推斷欄位與型別，樣本之後仍會照常匯入
+    let mut sample = Vec::new();
+    let mut sample_errors = Vec::new();
+    while sample.len() < options.sample_rows.max(1) {
+        match reader.next_record() {
+            Some(Ok(record)) => sample.push(record),
+            Some(Err(bad_row)) => sample_errors.push(bad_row),
+            None => break,
+        }
+    }
+
+    let source_names = if request.format == "jsonl" || request.format == "ndjson" {
+        // JSON Lines 的欄位為樣本中所有鍵的聯集，依首次出現順序
+        let mut keys: Vec<String> = Vec::new();
+        for record in &sample {
+            if let Some(Value::Object(object)) = record.values.first() {
+                for key in object.keys() {
+                    if !keys.contains(key) {
+                        keys.push(key.clone());
+                    }
+                }
+            }
+        }
+        for record in &mut sample {
+            if let Some(Value::Object(object)) = record.values.pop() {
+                record.values = keys.iter().map(|key| object.get(key).cloned().unwrap_or(Value::Null)).collect();
+            }
+        }
+        reader.set_columns(&keys);
+        keys
+    } else {
+        let field_count = sample.iter().map(|record| record.values.len()).max().unwrap_or(headers.len());
+        unique_column_names(&headers, field_count.max(headers.len()))
+    };
+
+    if source_names.is_empty() {
+        return Err("檔案中沒有可匯入的欄位".to_string());
+    }
+
+    let table = qualified_table_name(request);
+    let pool = DbPool::connect(&request.connection).await?;
+
+    // 決定每個欄位的目標名稱與型別
+    let mapping = match request.mode.as_str() {
+        "create" => {
+            let columns: Vec<(usize, String, ImportKind, String)> = source_names
+                .iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    let kind = infer_kind(sample.iter().filter_map(|record| record.values.get(index)));
+                    (index, name.clone(), kind, kind.sql_type(db_type).to_string())
+                })
+                .collect();
+
+            let definitions = columns
+                .iter()
+                .map(|(_, name, _, sql_type)| format!("    {} {sql_type}", quote_identifier(db_type, name)))
+                .collect::<Vec<_>>()
+                .join(",\n");
+            let create_sql = format!("CREATE TABLE {table} (\n{definitions}\n)");
+            if let Err(error) = pool.execute_transaction(&[create_sql]).await {
+                pool.close().await;
+                return Err(format!("建立表格失敗: {error}"));
+            }
+            columns
+        }
+        "append" => match map_existing_columns(request, &source_names).await {
+            Ok(columns) => columns,
+            Err(error) => {
+                pool.close().await;
+                return Err(error);
+            }
+        },
+        other => {
+            pool.close().await;
+            return Err(format!("不支援的匯入模式: {other}"));
+        }
+    };
+
+    report.columns = mapping
+        .iter()
+        .map(|(index, target, kind, sql_type)| ImportColumn {
+            source: source_names[*index].clone(),
+            target: target.clone(),
+            inferred_type: kind.name().to_string(),
+            sql_type: sql_type.clone(),
+        })
+        .collect();
+
+    let column_list = mapping
+        .iter()
+        .map(|(_, target, _, _)| quote_identifier(db_type, target))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // log 策略將所有錯誤行寫入檔案旁的 .errors.log
+    let error_log = if options.error_policy == "log" {
+        let log_path = format!("{}.errors.log", path.to_string_lossy());
+        let file = File::create(&log_path).map_err(|e| format!("無法建立錯誤記錄檔: {e}"))?;
+        report.error_log_path = Some(log_path);
+        Some(BufWriter::new(file))
+    } else {
+        None
+    };
+
+    let mut context = BatchContext {
+        app,
+        pool: &pool,
+        file_path: path.to_string_lossy().into_owned(),
+        total_bytes: std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0),
+        db_type: db_type.to_string(),
+        insert_prefix: format!("INSERT INTO {table} ({column_list}) VALUES\n"),
+        mapping: mapping.iter().map(|(index, _, kind, _)| (*index, *kind)).collect(),
+        policy: options.error_policy.clone(),
+        max_reported_errors: options.max_reported_errors,
+        error_log,
+    };
+
+    for bad_row in sample_errors {
+        context.record_bad_row(report, bad_row);
+    }
+
+    let batch_size = options.batch_size.max(1);
+    let mut batch: Vec<SourceRecord> = Vec::with_capacity(batch_size);
+    let mut pending = sample.into_iter();
+
+    while report.aborted.is_none() {
+        let next = match pending.next() {
+            Some(record) => Some(Ok(record)),
+            None => reader.next_record(),
+        };
+        match next {
+            Some(Ok(record)) => {
+                batch.push(record);
+                if batch.len() >= batch_size {
+                    context.flush(&mut batch, report, reader.bytes_read()).await;
+                }
+            }
+            Some(Err(bad_row)) => context.record_bad_row(report, bad_row),
+            None => break,
+        }
+    }
+
+    if report.aborted.is_none() {
+        context.flush(&mut batch, report, reader.bytes_read()).await;
+    }
+
+    if let Some(log) = &mut context.error_log {
+        let _ = log.flush();
+    }
+    pool.close().await;
+    Ok(())
+}
@@ -1,6 +1,32 @@
+use std::time::{Duration, Instant};
+
 use serde::{Deserialize, Serialize};
-use sqlx::{Column, Row, TypeInfo};
-use tauri::WebviewWindow;
+use sqlx::{Executor, Row};
+use tauri::{AppHandle, State, WebviewWindow};
+
+mod app_db;
+mod binary;
+mod columnar_export;
+mod columns;
+mod connection;
+mod ddl;
+mod dialect;
+mod dump;
+mod explain;
+mod export;
+mod history;
+mod import;
+mod notices;
+mod pg_values;
+mod query;
+mod snippets;
+mod spatial;
+mod sql_file;
+mod sql_guard;
+mod sql_splitter;
+mod sqlite_tools;
+mod values;
+mod xlsx_export;
 
 // 重新啟用 window-vibrancy，使用最新版本應該兼容 Tauri 2.0
 #[cfg(target_os = "windows")]
@@ -12,8 +38,15 @@ use window_vibrancy::{apply_vibrancy, NSVisualEffectMaterial, NSVisualEffectBlen
 #[cfg(target_os = "linux")]
 use window_vibrancy::apply_gtk_blur;
 
-#[derive(Debug, Serialize, Deserialize)]
+// 用戶端逾時比伺服器端多等的時間
+const CLIENT_TIMEOUT_GRACE: Duration = Duration::from_secs(2);
+// execute_query 預設最多返回的行數
+const DEFAULT_MAX_ROWS: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseConnection {
+    #[serde(default)]
+    pub id: String, // 前端保存的連接識別碼，用於查詢歷史
     pub name: String,
     pub db_type: String,
     pub host: String,
@@ -21,6 +54,71 @@ pub struct DatabaseConnection {
     pub database: String,
     pub username: String,
     pub password: String,
+    #[serde(default)]
+    pub attached_databases: Vec<AttachedDatabase>, // SQLite：每個連接都會附加的資料庫
+    #[serde(default)]
+    pub pragmas: Vec<SqlitePragma>, // SQLite：每個連接建立時設定的 PRAGMA，依順序套用
+    #[serde(default)]
+    pub extensions: Vec<SqliteExtension>, // SQLite：每個連接載入的擴充功能
+    #[serde(default)]
+    pub allow_extensions: bool, // 載入擴充功能會執行原生程式碼，需明確開啟
+    #[serde(default)]
+    pub read_only: bool, // 唯讀連接：在送出前拒絕寫入語句；SQLite 以 mode=ro 開啟，MySQL 與 PostgreSQL 設定唯讀工作階段
+    #[serde(default)]
+    pub immutable: bool, // SQLite：以 immutable=1 開啟，適用於不會再變動的快照檔案
+    #[serde(default)]
+    pub max_rows: Option<usize>, // 查詢最多返回的行數預設值，0 表示不限制
+    #[serde(default)]
+    pub query_timeout_ms: Option<u64>, // 查詢逾時的預設值，可由單次查詢覆寫
+    #[serde(skip)]
+    pub(crate) statement_timeout: Option<Duration>, // 由 execute_query 設定，連接時套用到伺服器端
+    #[serde(default)]
+    pub environment: String, // 'dev', 'staging', 'prod'（或 'production'）；staging 與 prod 執行高風險語句前需要確認
+    #[serde(default)]
+    pub encryption_key: Option<String>, // SQLCipher：在其他 PRAGMA 之前以 PRAGMA key 套用
+    #[serde(default)]
+    pub cipher: SqlCipherSettings,
+}
+
+impl DatabaseConnection {
+    pub(crate) fn is_read_only(&self) -> bool {
+        self.read_only || (self.db_type == "sqlite" && self.immutable)
+    }
+
+    pub(crate) fn is_protected_environment(&self) -> bool {
+        matches!(self.environment.trim().to_lowercase().as_str(), "staging" | "prod" | "production")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachedDatabase {
+    pub alias: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SqlitePragma {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SqliteExtension {
+    pub path: String,
+    pub entry_point: Option<String>, // 未指定時由 SQLite 依檔名推斷
+}
+
+// SQLCipher 相容性設定，未指定的項目使用 SQLCipher 預設值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SqlCipherSettings {
+    pub compatibility: Option<u32>, // 1 到 4，開啟舊版 SQLCipher 建立的檔案
+    pub page_size: Option<u32>,
+    pub kdf_iter: Option<u32>,
+    pub kdf_algorithm: Option<String>, // 'PBKDF2_HMAC_SHA1', 'PBKDF2_HMAC_SHA256', 'PBKDF2_HMAC_SHA512'
+    pub hmac_algorithm: Option<String>, // 'HMAC_SHA1', 'HMAC_SHA256', 'HMAC_SHA512'
+    pub use_hmac: Option<bool>,
+    pub plaintext_header_size: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -62,27 +160,69 @@ pub struct TestResult {
     pub success: bool,
     pub message: String,
     pub execution_time: u64,
+    pub read_only: bool,
+    pub error_kind: Option<connection::ErrorKind>, // 讓介面區分密鑰錯誤等需要使用者處理的失敗
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QueryRequest {
     pub connection: DatabaseConnection,
     pub sql: String,
+    #[serde(default)]
+    pub confirmation_token: Option<String>, // 受保護環境中確認執行高風險語句
+    #[serde(default)]
+    pub timeout_ms: Option<u64>, // 覆寫連接的 query_timeout_ms，0 表示不限制
+    #[serde(default)]
+    pub max_rows: Option<usize>, // 覆寫連接的 max_rows，0 表示返回所有資料列
+    #[serde(default)]
+    pub row_offset: usize, // 略過前幾行，用於載入截斷結果的後續資料列
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QueryResult {
     pub success: bool,
     pub columns: Vec<String>,
+    pub column_metadata: Vec<columns::ColumnMetadata>, // 與 columns 順序相同，包含型別與來源資料表
     pub rows: Vec<Vec<serde_json::Value>>,
+    pub truncated: bool, // 資料列超過上限，只返回 rows 中的部分
     pub affected_rows: Option<u64>,
     pub execution_time: u64,
     pub message: String,
+    pub read_only: bool,
+    pub confirmation: Option<sql_guard::ConfirmationRequired>, // 需要確認時語句不會執行
+    pub error_kind: Option<connection::ErrorKind>, // 逾時等需要介面特別處理的失敗
+    pub timing: Option<QueryTiming>, // 執行成功時各階段的時間
+    pub notices: Vec<notices::Notice>, // 伺服器在執行期間送出的通知與警告
+    pub result_sets: Vec<ResultSet>, // 批次或預存程序依序返回的結果，單一語句時為空
+}
+
+// 批次中的一個結果集或更新計數，頂層的 columns 與 rows 是第一個結果集的副本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultSet {
+    pub columns: Vec<String>,
+    pub column_metadata: Vec<columns::ColumnMetadata>, // 只有讀取到的型別，沒有來源資料表
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub truncated: bool,
+    pub affected_rows: Option<u64>, // 更新計數，結果集為 None
+}
+
+// execute_query 各階段花費的時間（毫秒）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryTiming {
+    pub connect_ms: f64, // 建立連接池並取得連接
+    pub execute_ms: f64, // 伺服器執行到返回第一行，非 SELECT 為整個執行時間
+    pub fetch_ms: f64, // 讀取其餘資料列，不含解碼
+    pub decode_ms: f64, // 把資料列解碼為 JSON 值
+    pub serialize_ms: f64, // 把結果序列化為傳給介面的 JSON
+    pub total_ms: f64,
+    pub rows_per_second: Option<f64>,
+    pub bytes_transferred: u64, // 序列化後傳給介面的資料量
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TableInfo {
     pub name: String,
+    pub schema: Option<String>, // SQLite 為 main、temp 或附加資料庫的別名
     pub row_count: u64,
     pub table_type: String,
 }
@@ -92,6 +232,7 @@ pub struct DatabaseTablesResult {
     pub success: bool,
     pub tables: Vec<TableInfo>,
     pub message: String,
+    pub read_only: bool,
 }
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
@@ -105,8 +246,8 @@ async fn test_database_connection(connection: DatabaseConnection) -> Result<Test
     let start_time = std::time::Instant::now();
     
     let result = match connection.db_type.as_str() {
-        "mysql" => test_mysql_connection(&connection).await,
-        "postgresql" => test_postgres_connection(&connection).await,
+        "mysql" => test_mysql_connection(&connection).await.map_err(connection::DbError::from),
+        "postgresql" => test_postgres_connection(&connection).await.map_err(connection::DbError::from),
         "sqlite" => test_sqlite_connection(&connection).await,
         _ => return Err("不支援的資料庫類型".to_string()),
     };
@@ -118,41 +259,119 @@ async fn test_database_connection(connection: DatabaseConnection) -> Result<Test
             success: true,
             message,
             execution_time,
+            read_only: connection.is_read_only(),
+            error_kind: None,
         }),
         Err(error) => Ok(TestResult {
             success: false,
-            message: format!("連接失敗: {error}"),
+            message: format!("連接失敗: {}", error.message),
             execution_time,
+            read_only: connection.is_read_only(),
+            error_kind: error.kind,
         }),
     }
 }
 
 #[tauri::command]
-async fn execute_query(request: QueryRequest) -> Result<QueryResult, String> {
+async fn execute_query(
+    app: AppHandle,
+    app_db: State<'_, app_db::AppDatabase>,
+    confirmations: State<'_, sql_guard::PendingConfirmations>,
+    request: QueryRequest,
+) -> Result<QueryResult, String> {
     let start_time = std::time::Instant::now();
+    let read_only = request.connection.is_read_only();
     
-    let result = match request.connection.db_type.as_str() {
-        "mysql" => execute_mysql_query(&request.connection, &request.sql).await,
-        "postgresql" => execute_postgres_query(&request.connection, &request.sql).await,
-        "sqlite" => execute_sqlite_query(&request.connection, &request.sql).await,
-        _ => return Err("不支援的資料庫類型".to_string()),
+    let result = match sql_guard::check_read_only(&request.connection, &request.sql) {
+        Err(error) => Err(connection::DbError::from(error)),
+        Ok(()) => {
+            let token = request.confirmation_token.as_deref();
+            if let Some(confirmation) = sql_guard::confirmation_required(&request.connection, &request.sql, token, &confirmations).await {
+                let count = confirmation.statements.len();
+                let environment = &confirmation.environment;
+                return Ok(QueryResult {
+                    success: false,
+                    columns: vec![],
+                    column_metadata: vec![],
+                    rows: vec![],
+                    truncated: false,
+                    affected_rows: None,
+                    execution_time: start_time.elapsed().as_millis() as u64,
+                    message: format!("{environment} 環境中有 {count} 條高風險語句，確認後才會執行"),
+                    read_only,
+                    confirmation: Some(confirmation),
+                    error_kind: None,
+                    timing: None,
+                    notices: vec![],
+                    result_sets: vec![],
+                });
+            }
+            execute_with_timeout(&request).await
+        }
     };
     
     let execution_time = start_time.elapsed().as_millis() as u64;
     
-    match result {
+    let query_result = match result {
         Ok(mut query_result) => {
             query_result.execution_time = execution_time;
-            Ok(query_result)
+            query_result.read_only = read_only;
+            if let Some(timing) = &mut query_result.timing {
+                timing.total_ms = millis(start_time.elapsed());
+            }
+            query_result
         }
-        Err(error) => Ok(QueryResult {
+        Err(error) => QueryResult {
             success: false,
             columns: vec![],
+            column_metadata: vec![],
             rows: vec![],
+            truncated: false,
             affected_rows: None,
             execution_time,
-            message: format!("查詢錯誤: {error}"),
-        }),
+            error_kind: error.kind,
+            timing: None,
+            notices: vec![],
+            result_sets: vec![],
+            message: format!("查詢錯誤: {}", error.message),
+            read_only,
+            confirmation: None,
+        },
+    };
+
+    history::record_query(&app, &app_db, &request.connection, &request.sql, &query_result).await;
+    Ok(query_result)
+}
+
+// 逾時同時套用在伺服器端與用戶端；用戶端多等一段時間，讓伺服器先取消查詢並返回錯誤
+async fn execute_with_timeout(request: &QueryRequest) -> Result<QueryResult, connection::DbError> {
+    let max_rows = request.max_rows.or(request.connection.max_rows).unwrap_or(DEFAULT_MAX_ROWS);
+    let window = query::RowWindow {
+        offset: request.row_offset,
+        limit: Some(max_rows).filter(|max_rows| *max_rows > 0),
+    };
+    let timeout_ms = request.timeout_ms.or(request.connection.query_timeout_ms).filter(|ms| *ms > 0);
+    let Some(limit) = timeout_ms.map(Duration::from_millis) else {
+        return execute_on_connection(&request.connection, &request.sql, window).await;
+    };
+
+    let mut connection = request.connection.clone();
+    connection.statement_timeout = Some(limit);
+    let db_type = connection.db_type.as_str();
+    match tokio::time::timeout(limit + CLIENT_TIMEOUT_GRACE, execute_on_connection(&connection, &request.sql, window)).await {
+        Ok(Err(error)) if connection::is_timeout_error(db_type, &error.message) => Err(connection::timeout_error(limit)),
+        Ok(result) => result,
+        // 放棄等待會關閉連接，伺服器端的查詢隨之取消
+        Err(_) => Err(connection::timeout_error(limit)),
+    }
+}
+
+async fn execute_on_connection(connection: &DatabaseConnection, sql: &str, window: query::RowWindow) -> Result<QueryResult, connection::DbError> {
+    match connection.db_type.as_str() {
+        "mysql" => Ok(execute_mysql_query(connection, sql, window).await?),
+        "postgresql" => Ok(execute_postgres_query(connection, sql, window).await?),
+        "sqlite" => execute_sqlite_query(connection, sql, window).await,
+        _ => Err("不支援的資料庫類型".to_string().into()),
     }
 }
 
@@ -164,7 +383,7 @@ async fn select_sqlite_file() -> Result<Option<String>, String> {
 
 #[tauri::command]
 async fn get_database_tables(connection: DatabaseConnection) -> Result<DatabaseTablesResult, String> {
-    match connection.db_type.as_str() {
+    let result = match connection.db_type.as_str() {
         "sqlite" => get_sqlite_tables(&connection).await,
         "mysql" => get_mysql_tables(&connection).await,
         "postgresql" => get_postgres_tables(&connection).await,
@@ -172,8 +391,14 @@ async fn get_database_tables(connection: DatabaseConnection) -> Result<DatabaseT
             success: false,
             tables: vec![],
             message: "不支援的資料庫類型".to_string(),
+            read_only: false,
         }),
-    }
+    };
+
+    result.map(|mut tables_result| {
+        tables_result.read_only = connection.is_read_only();
+        tables_result
+    })
 }
 
 // 透明效果設置命令 - 支持所有三種方案
@@ -370,112 +595,54 @@ async fn clear_transparency_effect(window: WebviewWindow, method: String) -> Res
     }
 }
 
-async fn execute_sqlite_query(connection: &DatabaseConnection, sql: &str) -> Result<QueryResult, String> {
-    let database_path = if connection.database.is_empty() {
-        ":memory:".to_string()
-    } else {
-        connection.database.clone()
-    };
+async fn execute_sqlite_query(connection: &DatabaseConnection, sql: &str, window: query::RowWindow) -> Result<QueryResult, connection::DbError> {
+    let connecting = Instant::now();
+    let pool = connection::connect_sqlite(connection).await?;
+    let connect = connecting.elapsed();
 
-    // 使用連接池設置優化性能
-    let pool = sqlx::sqlite::SqlitePool::connect_with(
-        sqlx::sqlite::SqliteConnectOptions::new()
-            .filename(&database_path)
-            .create_if_missing(false)
-    )
-    .await
-    .map_err(|e| format!("SQLite 連接錯誤: {e}"))?;
+    if query::returns_many_results(sql, "sqlite") {
+        let result = match pool.acquire().await {
+            Ok(mut conn) => {
+                let rows_affected = sqlx::sqlite::SqliteQueryResult::rows_affected;
+                query::stream_result_sets::<sqlx::Sqlite>(&mut conn, sql, values::sqlite_row_values, rows_affected, window).await
+            }
+            Err(e) => Err(format!("查詢執行錯誤: {e}")),
+        };
+        let mut notices = vec![];
+        if result.is_ok() {
+            for statement in sql_splitter::split_statements(sql, "sqlite") {
+                notices.extend(notices::sqlite_notices(&pool, &statement).await);
+            }
+        }
+        pool.close().await;
+        return Ok(batch_query_result("sqlite", sql, result?, notices, connect));
+    }
 
-    // 檢查是否為 SELECT 查詢
+    // 檢查是否為返回資料列的查詢（SELECT、WITH、VALUES、PRAGMA 等）
     let trimmed_sql = sql.trim().to_lowercase();
-    let is_select = trimmed_sql.starts_with("select");
+    let is_select = sql_guard::returns_rows(sql, "sqlite");
 
     if is_select {
-        // SELECT 查詢，返回結果集
-        let rows = sqlx::query(sql)
-            .fetch_all(&pool)
-            .await
-            .map_err(|e| format!("查詢執行錯誤: {e}"))?;
-
-        if rows.is_empty() {
-            pool.close().await;
-            return Ok(QueryResult {
-                success: true,
-                columns: vec![],
-                rows: vec![],
-                affected_rows: Some(0),
-                execution_time: 0,
-                message: "查詢成功，無結果".to_string(),
-            });
-        }
-
-        // 獲取列名
-        let columns: Vec<String> = rows[0]
-            .columns()
-            .iter()
-            .map(|col| col.name().to_string())
-            .collect();
-
-        // 轉換行數據 - 優化版本
-        let mut result_rows = Vec::with_capacity(rows.len());
-        for row in rows {
-            let mut row_data = Vec::with_capacity(row.columns().len());
-            for (i, column) in row.columns().iter().enumerate() {
-                let value: serde_json::Value = match column.type_info().name() {
-                    "TEXT" | "VARCHAR" => {
-                        match row.try_get::<Option<String>, _>(i) {
-                            Ok(Some(s)) => serde_json::Value::String(s),
-                            Ok(None) => serde_json::Value::Null,
-                            Err(_) => serde_json::Value::Null,
-                        }
-                    }
-                    "INTEGER" => {
-                        match row.try_get::<Option<i64>, _>(i) {
-                            Ok(Some(n)) => serde_json::Value::Number(serde_json::Number::from(n)),
-                            Ok(None) => serde_json::Value::Null,
-                            Err(_) => serde_json::Value::Null,
-                        }
-                    }
-                    "REAL" => {
-                        match row.try_get::<Option<f64>, _>(i) {
-                            Ok(Some(n)) => serde_json::Number::from_f64(n)
-                                .map(serde_json::Value::Number)
-                                .unwrap_or(serde_json::Value::Null),
-                            Ok(None) => serde_json::Value::Null,
-                            Err(_) => serde_json::Value::Null,
-                        }
-                    }
-                    _ => {
-                        // 其他類型嘗試轉為字符串
-                        match row.try_get::<Option<String>, _>(i) {
-                            Ok(Some(s)) => serde_json::Value::String(s),
-                            Ok(None) => serde_json::Value::Null,
-                            Err(_) => serde_json::Value::Null,
-                        }
-                    }
-                };
-                row_data.push(value);
-            }
-            result_rows.push(row_data);
-        }
-
+        // 返回結果集
+        let mut collected = query::CollectedRows::with_window(window);
+        let result = query::stream_rows(&pool, sql, values::sqlite_row_values, &mut collected).await;
+        let column_metadata = match (&result, pool.acquire().await) {
+            (Ok(_), Ok(mut conn)) => columns::sqlite_columns(&mut conn, sql, &collected.columns).await,
+            _ => vec![],
+        };
+        let notices = notices::sqlite_notices(&pool, sql).await;
         pool.close().await;
+        result?;
 
-        let row_count = result_rows.len();
-        Ok(QueryResult {
-            success: true,
-            columns,
-            rows: result_rows,
-            affected_rows: Some(row_count as u64),
-            execution_time: 0,
-            message: format!("查詢成功，返回 {row_count} 行"),
-        })
+        Ok(select_query_result(collected, column_metadata, notices, connect))
     } else {
         // 非 SELECT 查詢 (INSERT, UPDATE, DELETE, CREATE, etc.)
+        let executing = Instant::now();
         let result = sqlx::query(sql)
             .execute(&pool)
             .await
             .map_err(|e| format!("執行錯誤: {e}"))?;
+        let execute = executing.elapsed();
 
         let affected_rows = result.rows_affected();
         let message = if trimmed_sql.starts_with("create") {
@@ -490,111 +657,344 @@ async fn execute_sqlite_query(connection: &DatabaseConnection, sql: &str) -> Res
             format!("執行成功，影響 {affected_rows} 行")
         };
 
+        let notices = notices::sqlite_notices(&pool, sql).await;
         pool.close().await;
 
         Ok(QueryResult {
             success: true,
             columns: vec![],
+            column_metadata: vec![],
             rows: vec![],
+            truncated: false,
             affected_rows: Some(affected_rows),
             execution_time: 0,
             message,
+            read_only: false,
+            confirmation: None,
+            error_kind: None,
+            timing: Some(QueryTiming {
+                connect_ms: millis(connect),
+                execute_ms: millis(execute),
+                ..QueryTiming::default()
+            }),
+            notices,
+            result_sets: vec![],
         })
     }
 }
 
-async fn execute_mysql_query(connection: &DatabaseConnection, sql: &str) -> Result<QueryResult, String> {
-    let database_url = format!(
-        "mysql://{}:{}@{}:{}/{}",
-        connection.username,
-        connection.password,
-        connection.host,
-        connection.port,
-        connection.database
-    );
+async fn execute_mysql_query(connection: &DatabaseConnection, sql: &str, window: query::RowWindow) -> Result<QueryResult, String> {
+    let connecting = Instant::now();
+    let pool = connection::connect_mysql(connection).await?;
+    // 警告只能在同一個連接上讀取，所有語句都使用這個連接
+    let mut conn = match pool.acquire().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            pool.close().await;
+            return Err(format!("MySQL 連接錯誤: {e}"));
+        }
+    };
+    let connect = connecting.elapsed();
+
+    // 唯讀連接把整個請求放在唯讀交易中，不只依賴工作階段的預設值
+    let read_only = connection.is_read_only();
+    let result = match read_only {
+        true => match conn.execute("START TRANSACTION READ ONLY").await {
+            Ok(_) => run_mysql_query(&mut conn, sql, window, connect).await,
+            Err(e) => Err(format!("查詢執行錯誤: {e}")),
+        },
+        false => run_mysql_query(&mut conn, sql, window, connect).await,
+    };
+    if read_only {
+        let _ = conn.execute("ROLLBACK").await;
+    }
+    drop(conn);
+    pool.close().await;
+    result
+}
 
-    let pool = sqlx::mysql::MySqlPool::connect(&database_url)
-        .await
-        .map_err(|e| format!("MySQL 連接錯誤: {e}"))?;
+async fn run_mysql_query(
+    conn: &mut sqlx::MySqlConnection,
+    sql: &str,
+    window: query::RowWindow,
+    connect: Duration,
+) -> Result<QueryResult, String> {
+    if query::returns_many_results(sql, "mysql") {
+        let rows_affected = sqlx::mysql::MySqlQueryResult::rows_affected;
+        let mut results = Vec::new();
+        let mut notices = Vec::new();
+        // SHOW WARNINGS 只包含上一條語句的警告，逐條執行並在每條之後讀取
+        for statement in sql_splitter::split_statements(sql, "mysql") {
+            // 預存程序可能返回多個結果集，只能以文字協定取得
+            if sql_guard::main_keyword(&statement, "mysql") == "CALL" {
+                results.extend(query::stream_result_sets::<sqlx::MySql>(&mut *conn, &statement, values::mysql_row_values, rows_affected, window).await?);
+            } else {
+                results.push(query::stream_statement_result::<sqlx::MySql>(&mut *conn, &statement, values::mysql_row_values, rows_affected, window).await?);
+            }
+            notices.extend(notices::mysql_warnings(&mut *conn).await);
+        }
+        return Ok(batch_query_result("mysql", sql, results, notices, connect));
+    }
 
-    // 簡化版：目前只支持基本查詢
-    let trimmed_sql = sql.trim().to_lowercase();
-    let is_select = trimmed_sql.starts_with("select");
+    let is_select = sql_guard::returns_rows(sql, "mysql");
 
     if is_select {
-        let rows = sqlx::query(sql)
-            .fetch_all(&pool)
+        let mut collected = query::CollectedRows::with_window(window);
+        query::stream_rows_on::<sqlx::MySql>(&mut *conn, sql, values::mysql_row_values, &mut collected).await?;
+        let notices = notices::mysql_warnings(&mut *conn).await;
+        let column_metadata = columns::mysql_columns(&mut *conn, sql, &collected.columns).await;
+        Ok(select_query_result(collected, column_metadata, notices, connect))
+    } else {
+        let executing = Instant::now();
+        let result = sqlx::query(sql)
+            .execute(&mut *conn)
             .await
-            .map_err(|e| format!("查詢執行錯誤: {e}"))?;
-
-        if rows.is_empty() {
-            return Ok(QueryResult {
-                success: true,
-                columns: vec![],
-                rows: vec![],
-                affected_rows: Some(0),
-                execution_time: 0,
-                message: "查詢成功，無結果".to_string(),
-            });
-        }
-
-        let columns: Vec<String> = rows[0]
-            .columns()
-            .iter()
-            .map(|col| col.name().to_string())
-            .collect();
+            .map_err(|e| format!("執行錯誤: {e}"))?;
+        let execute = executing.elapsed();
+        let notices = notices::mysql_warnings(&mut *conn).await;
 
+        let rows_affected = result.rows_affected();
         Ok(QueryResult {
             success: true,
-            columns,
-            rows: vec![], // 簡化版，暫不處理複雜的數據轉換
-            affected_rows: Some(rows.len() as u64),
+            columns: vec![],
+            column_metadata: vec![],
+            rows: vec![],
+            truncated: false,
+            affected_rows: Some(rows_affected),
             execution_time: 0,
-            message: format!("查詢成功，返回 {} 行", rows.len()),
+            message: format!("執行成功，影響 {rows_affected} 行"),
+            read_only: false,
+            confirmation: None,
+            error_kind: None,
+            timing: Some(QueryTiming {
+                connect_ms: millis(connect),
+                execute_ms: millis(execute),
+                ..QueryTiming::default()
+            }),
+            notices,
+            result_sets: vec![],
         })
+    }
+}
+
+async fn execute_postgres_query(connection: &DatabaseConnection, sql: &str, window: query::RowWindow) -> Result<QueryResult, String> {
+    let connecting = Instant::now();
+    let pool = connection::connect_postgres(connection).await?;
+    let mut conn = match pool.acquire().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            pool.close().await;
+            return Err(format!("PostgreSQL 連接錯誤: {e}"));
+        }
+    };
+    let connect = connecting.elapsed();
+
+    // 唯讀連接把整個請求放在唯讀交易中，即使語句重設了工作階段的預設值也無法寫入
+    let read_only = connection.is_read_only();
+    let (result, notices) = notices::with_postgres_notices(async {
+        if read_only {
+            conn.execute("BEGIN READ ONLY")
+                .await
+                .map_err(|e| format!("查詢執行錯誤: {e}"))?;
+        }
+        let result = run_postgres_query(&mut conn, sql, window, connect).await;
+        if read_only {
+            let _ = conn.execute("ROLLBACK").await;
+        }
+        result
+    })
+    .await;
+    drop(conn);
+    pool.close().await;
+
+    let mut query_result = result?;
+    query_result.notices = notices;
+    Ok(query_result)
+}
+
+// 通知由 execute_postgres_query 在整個請求期間收集
+async fn run_postgres_query(
+    conn: &mut sqlx::PgConnection,
+    sql: &str,
+    window: query::RowWindow,
+    connect: Duration,
+) -> Result<QueryResult, String> {
+    if query::returns_many_results(sql, "postgresql") {
+        let rows_affected = sqlx::postgres::PgQueryResult::rows_affected;
+        let mut results = Vec::new();
+        // 簡單協定以文字格式傳回所有值，逐條以擴充協定執行才能與單一查詢一樣依型別解碼
+        for statement in sql_splitter::split_statements(sql, "postgresql") {
+            results.push(query::stream_statement_result::<sqlx::Postgres>(&mut *conn, &statement, values::postgres_row_values, rows_affected, window).await?);
+        }
+        return Ok(batch_query_result("postgresql", sql, results, vec![], connect));
+    }
+
+    let is_select = sql_guard::returns_rows(sql, "postgresql");
+
+    if is_select {
+        let mut collected = query::CollectedRows::with_window(window);
+        query::stream_rows_on::<sqlx::Postgres>(&mut *conn, sql, values::postgres_row_values, &mut collected).await?;
+        let column_metadata = columns::postgres_columns(&mut *conn, sql, &collected.columns).await;
+        Ok(select_query_result(collected, column_metadata, vec![], connect))
     } else {
+        let executing = Instant::now();
         let result = sqlx::query(sql)
-            .execute(&pool)
+            .execute(&mut *conn)
             .await
             .map_err(|e| format!("執行錯誤: {e}"))?;
-        
+        let execute = executing.elapsed();
+
         let rows_affected = result.rows_affected();
         Ok(QueryResult {
             success: true,
             columns: vec![],
+            column_metadata: vec![],
             rows: vec![],
+            truncated: false,
             affected_rows: Some(rows_affected),
             execution_time: 0,
             message: format!("執行成功，影響 {rows_affected} 行"),
+            read_only: false,
+            confirmation: None,
+            error_kind: None,
+            timing: Some(QueryTiming {
+                connect_ms: millis(connect),
+                execute_ms: millis(execute),
+                ..QueryTiming::default()
+            }),
+            notices: vec![],
+            result_sets: vec![],
         })
     }
 }
 
-async fn execute_postgres_query(_connection: &DatabaseConnection, _sql: &str) -> Result<QueryResult, String> {
-    // 類似 MySQL 的實現，暫時簡化
-    Ok(QueryResult {
-        success: false,
-        columns: vec![],
-        rows: vec![],
-        affected_rows: None,
+// SELECT 查詢的結果，所有資料庫共用
+fn select_query_result(
+    collected: query::CollectedRows,
+    column_metadata: Vec<columns::ColumnMetadata>,
+    notices: Vec<notices::Notice>,
+    connect: Duration,
+) -> QueryResult {
+    let row_count = collected.rows.len();
+    let fetch = collected.timing;
+    let serializing = Instant::now();
+    let mut bytes = query::ByteCounter::default();
+    let _ = serde_json::to_writer(&mut bytes, &collected.rows);
+    let reading = fetch.first_row + fetch.fetch + fetch.decode;
+    let rows_read = (collected.skipped + row_count) as f64;
+    let timing = QueryTiming {
+        connect_ms: millis(connect),
+        execute_ms: millis(fetch.first_row),
+        fetch_ms: millis(fetch.fetch),
+        decode_ms: millis(fetch.decode),
+        serialize_ms: millis(serializing.elapsed()),
+        total_ms: 0.0,
+        rows_per_second: (reading > Duration::ZERO).then(|| rows_read / reading.as_secs_f64()),
+        bytes_transferred: bytes.0,
+    };
+
+    let message = if collected.truncated {
+        format!("查詢成功，返回 {row_count} 行（已達上限，結果已截斷）")
+    } else if row_count == 0 {
+        "查詢成功，無結果".to_string()
+    } else {
+        format!("查詢成功，返回 {row_count} 行")
+    };
+
+    QueryResult {
+        success: true,
+        columns: collected.columns.into_iter().map(|column| column.name).collect(),
+        column_metadata,
+        rows: collected.rows,
+        truncated: collected.truncated,
+        affected_rows: Some(row_count as u64),
         execution_time: 0,
-        message: "PostgreSQL 查詢功能開發中".to_string(),
-    })
+        message,
+        read_only: false,
+        confirmation: None,
+        error_kind: None,
+        timing: Some(timing),
+        notices,
+        result_sets: vec![],
+    }
 }
 
-async fn test_mysql_connection(connection: &DatabaseConnection) -> Result<String, String> {
-    let database_url = format!(
-        "mysql://{}:{}@{}:{}/{}",
-        connection.username,
-        connection.password,
-        connection.host,
-        connection.port,
-        connection.database
-    );
+// 批次或預存程序的結果，依伺服器返回的順序保留每個結果集與更新計數
+fn batch_query_result(
+    db_type: &str,
+    sql: &str,
+    results: Vec<query::StatementResult>,
+    notices: Vec<notices::Notice>,
+    connect: Duration,
+) -> QueryResult {
+    let mut fetch = query::FetchTiming::default();
+    let mut rows_read = 0usize;
+    let mut result_sets = Vec::with_capacity(results.len());
+    // 每條語句剛好對應一個結果時，沒有資料列的讀取語句仍算結果集（SQLite 此時回報的是上一條語句的影響行數）
+    let statements = sql_splitter::split_statements(sql, db_type);
+    let aligned = statements.len() == results.len();
+    for (i, result) in results.into_iter().enumerate() {
+        let collected = result.collected;
+        fetch.first_row += collected.timing.first_row;
+        fetch.fetch += collected.timing.fetch;
+        fetch.decode += collected.timing.decode;
+        rows_read += collected.skipped + collected.rows.len();
+
+        let returns_rows = !collected.columns.is_empty() || (aligned && sql_guard::returns_rows(&statements[i], db_type));
+        result_sets.push(ResultSet {
+            column_metadata: columns::streamed_metadata(db_type, &collected.columns),
+            columns: collected.columns.into_iter().map(|column| column.name).collect(),
+            rows: collected.rows,
+            truncated: collected.truncated,
+            affected_rows: (!returns_rows).then_some(result.rows_affected),
+        });
+    }
 
-    let pool = sqlx::mysql::MySqlPool::connect(&database_url)
-        .await
-        .map_err(|e| format!("MySQL 連接錯誤: {e}"))?;
+    let serializing = Instant::now();
+    let mut bytes = query::ByteCounter::default();
+    let _ = serde_json::to_writer(&mut bytes, &result_sets);
+    let reading = fetch.first_row + fetch.fetch + fetch.decode;
+    let timing = QueryTiming {
+        connect_ms: millis(connect),
+        execute_ms: millis(fetch.first_row),
+        fetch_ms: millis(fetch.fetch),
+        decode_ms: millis(fetch.decode),
+        serialize_ms: millis(serializing.elapsed()),
+        total_ms: 0.0,
+        rows_per_second: (reading > Duration::ZERO).then(|| rows_read as f64 / reading.as_secs_f64()),
+        bytes_transferred: bytes.0,
+    };
+
+    // 頂層欄位沿用第一個結果集，只顯示單一結果的介面仍可使用
+    let first = result_sets.iter().find(|set| set.affected_rows.is_none()).cloned();
+    let update_counts: Vec<u64> = result_sets.iter().filter_map(|set| set.affected_rows).collect();
+    let row_sets = result_sets.len() - update_counts.len();
+    let message = format!("批次執行成功，返回 {row_sets} 個結果集與 {} 個更新計數", update_counts.len());
+
+    QueryResult {
+        success: true,
+        columns: first.as_ref().map(|set| set.columns.clone()).unwrap_or_default(),
+        column_metadata: first.as_ref().map(|set| set.column_metadata.clone()).unwrap_or_default(),
+        truncated: result_sets.iter().any(|set| set.truncated),
+        rows: first.map(|set| set.rows).unwrap_or_default(),
+        affected_rows: Some(update_counts.iter().sum()),
+        execution_time: 0,
+        message,
+        read_only: false,
+        confirmation: None,
+        error_kind: None,
+        timing: Some(timing),
+        notices,
+        result_sets,
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+async fn test_mysql_connection(connection: &DatabaseConnection) -> Result<String, String> {
+    let pool = connection::connect_mysql(connection).await?;
 
     // 測試查詢
     let row = sqlx::query("SELECT VERSION() as version")
@@ -611,33 +1011,8 @@ async fn test_mysql_connection(connection: &DatabaseConnection) -> Result<String
 }
 
 async fn test_postgres_connection(connection: &DatabaseConnection) -> Result<String, String> {
-    // 檢查是否為 Supabase 或雲端資料庫
-    let is_cloud_db = connection.host.contains("supabase.co") || 
-                      connection.host.contains("amazonaws.com") ||
-                      connection.host.contains("azure.com") ||
-                      connection.host.contains("googleusercontent.com");
-
-    let database_url = if is_cloud_db {
-        // 雲端資料庫通常需要 SSL
-        format!(
-            "postgres://{}:{}@{}:{}/{}?sslmode=require&connect_timeout=10",
-            connection.username,
-            connection.password,
-            connection.host,
-            connection.port,
-            connection.database
-        )
-    } else {
-        // 本地資料庫
-        format!(
-            "postgres://{}:{}@{}:{}/{}?connect_timeout=10",
-            connection.username,
-            connection.password,
-            connection.host,
-            connection.port,
-            connection.database
-        )
-    };
+    // 雲端資料庫（Supabase、AWS 等）會自動加上 SSL 參數
+    let database_url = connection::postgres_url(connection);
 
     let pool = sqlx::postgres::PgPool::connect(&database_url)
         .await
@@ -676,19 +1051,9 @@ async fn test_postgres_connection(connection: &DatabaseConnection) -> Result<Str
     Ok(format!("PostgreSQL 連接成功！版本: {version}"))
 }
 
-async fn test_sqlite_connection(connection: &DatabaseConnection) -> Result<String, String> {
-    // SQLite 使用檔案路徑，不需要網路連接
-    let database_path = if connection.database.is_empty() {
-        ":memory:".to_string() // 內存資料庫
-    } else {
-        connection.database.clone()
-    };
-
-    let database_url = format!("sqlite:{database_path}");
-
-    let pool = sqlx::sqlite::SqlitePool::connect(&database_url)
-        .await
-        .map_err(|e| format!("SQLite 連接錯誤: {e}"))?;
+async fn test_sqlite_connection(connection: &DatabaseConnection) -> Result<String, connection::DbError> {
+    // SQLite 使用檔案路徑，不需要網路連接；附加資料庫、PRAGMA 與擴充功能也一併驗證
+    let pool = connection::connect_sqlite(connection).await?;
 
     // 測試查詢
     let row = sqlx::query("SELECT sqlite_version() as version")
@@ -705,58 +1070,67 @@ async fn test_sqlite_connection(connection: &DatabaseConnection) -> Result<Strin
 }
 
 async fn get_sqlite_tables(connection: &DatabaseConnection) -> Result<DatabaseTablesResult, String> {
-    let database_path = if connection.database.is_empty() {
-        ":memory:".to_string()
-    } else {
-        connection.database.clone()
-    };
+    let pool = connection::connect_sqlite(connection).await?;
 
-    let pool = sqlx::sqlite::SqlitePool::connect(&database_path)
-        .await
-        .map_err(|e| format!("SQLite 連接錯誤: {e}"))?;
-
-    // 獲取所有資料庫對象（表格、檢視表、索引、觸發器）
-    let table_rows = sqlx::query(
-        "SELECT name, type FROM sqlite_master 
-         WHERE type IN ('table', 'view', 'index', 'trigger') 
-         AND (name NOT LIKE 'sqlite_%' OR name IN ('sqlite_sequence', 'sqlite_stat1', 'sqlite_stat2', 'sqlite_stat3', 'sqlite_stat4'))
-         ORDER BY type, name"
-    )
+    // main、temp 與所有附加的資料庫
+    let schema_rows = sqlx::query("PRAGMA database_list")
         .fetch_all(&pool)
         .await
-        .map_err(|e| format!("查詢資料庫對象錯誤: {e}"))?;
+        .map_err(|e| format!("查詢資料庫列表錯誤: {e}"))?;
 
     let mut tables = Vec::new();
 
-    for row in table_rows {
-        let object_name: String = row.try_get("name")
-            .map_err(|e| format!("取得對象名稱錯誤: {e}"))?;
-        let object_type: String = row.try_get("type")
-            .map_err(|e| format!("取得對象類型錯誤: {e}"))?;
-
-        // 獲取記錄數（只對表格和檢視表）
-        let row_count = if object_type == "table" || object_type == "view" {
-            let count_query = format!("SELECT COUNT(*) as count FROM \"{object_name}\"");
-            let count_result = sqlx::query(&count_query)
-                .fetch_one(&pool)
-                .await;
-
-            match count_result {
-                Ok(row) => {
-                    row.try_get::<i64, _>("count").unwrap_or(0) as u64
+    for schema_row in schema_rows {
+        let schema: String = schema_row.try_get("name")
+            .map_err(|e| format!("取得資料庫名稱錯誤: {e}"))?;
+        let quoted_schema = dialect::quote_identifier("sqlite", &schema);
+        let master_table = if schema == "temp" { "sqlite_temp_master" } else { "sqlite_master" };
+
+        // 獲取所有資料庫對象（表格、檢視表、索引、觸發器）
+        let table_rows = sqlx::query(&format!(
+            "SELECT name, type FROM {quoted_schema}.{master_table}
+             WHERE type IN ('table', 'view', 'index', 'trigger')
+             AND (name NOT LIKE 'sqlite_%' OR name IN ('sqlite_sequence', 'sqlite_stat1', 'sqlite_stat2', 'sqlite_stat3', 'sqlite_stat4'))
+             ORDER BY type, name"
+        ))
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| format!("查詢資料庫對象錯誤: {e}"))?;
+
+        for row in table_rows {
+            let object_name: String = row.try_get("name")
+                .map_err(|e| format!("取得對象名稱錯誤: {e}"))?;
+            let object_type: String = row.try_get("type")
+                .map_err(|e| format!("取得對象類型錯誤: {e}"))?;
+
+            // 獲取記錄數（只對表格和檢視表）
+            let row_count = if object_type == "table" || object_type == "view" {
+                let count_query = format!(
+                    "SELECT COUNT(*) as count FROM {quoted_schema}.{}",
+                    dialect::quote_identifier("sqlite", &object_name)
+                );
+                let count_result = sqlx::query(&count_query)
+                    .fetch_one(&pool)
+                    .await;
+
+                match count_result {
+                    Ok(row) => {
+                        row.try_get::<i64, _>("count").unwrap_or(0) as u64
+                    }
+                    Err(_) => 0, // 如果查詢失敗，設為 0
                 }
-                Err(_) => 0, // 如果查詢失敗，設為 0
-            }
-        } else {
-            // 索引和觸發器不計算記錄數
-            0
-        };
-
-        tables.push(TableInfo {
-            name: object_name,
-            row_count,
-            table_type: object_type,
-        });
+            } else {
+                // 索引和觸發器不計算記錄數
+                0
+            };
+
+            tables.push(TableInfo {
+                name: object_name,
+                schema: Some(schema.clone()),
+                row_count,
+                table_type: object_type,
+            });
+        }
     }
 
     pool.close().await;
@@ -766,6 +1140,7 @@ async fn get_sqlite_tables(connection: &DatabaseConnection) -> Result<DatabaseTa
         success: true,
         tables,
         message: format!("找到 {table_count} 個資料庫對象"),
+        read_only: false,
     })
 }
 
@@ -774,6 +1149,7 @@ async fn get_mysql_tables(_connection: &DatabaseConnection) -> Result<DatabaseTa
         success: false,
         tables: vec![],
         message: "MySQL 表格列表功能開發中".to_string(),
+        read_only: false,
     })
 }
 
@@ -782,6 +1158,7 @@ async fn get_postgres_tables(_connection: &DatabaseConnection) -> Result<Databas
         success: false,
         tables: vec![],
         message: "PostgreSQL 表格列表功能開發中".to_string(),
+        read_only: false,
     })
 }
 
@@ -790,6 +1167,9 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .manage(sql_file::SqlFileRuns::default())
+        .manage(sql_guard::PendingConfirmations::default())
+        .manage(app_db::AppDatabase::default())
         .setup(|_app| {
             println!("Serphic 已啟動，請在設置中選擇透明效果類型");
             Ok(())
@@ -800,6 +1180,25 @@ pub fn run() {
             execute_query, 
             select_sqlite_file, 
             get_database_tables,
+            ddl::get_object_ddl,
+            explain::explain_query,
+            binary::fetch_cell,
+            binary::upload_cell,
+            export::export_query,
+            dump::dump_database,
+            history::search_query_history,
+            history::rerun_query_history,
+            history::purge_query_history,
+            snippets::search_snippets,
+            snippets::save_snippet,
+            snippets::delete_snippets,
+            snippets::rename_snippet_folder,
+            snippets::export_snippets,
+            snippets::import_snippets,
+            import::import_file,
+            sql_file::run_sql_file,
+            sql_file::stop_sql_file,
+            sqlite_tools::sqlite_maintenance,
             set_transparency_effect,
             clear_transparency_effect
         ])
@@ -0,0 +1,198 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use tracing::field::{Field, Visit};
+use tracing::instrument::WithSubscriber;
+use tracing::{span, Event, Level, Metadata};
+
+// sqlx 把 PostgreSQL 的 NoticeResponse 以這個 target 的 tracing 事件送出
+const POSTGRES_NOTICE_TARGET: &str = "sqlx::postgres::notice";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoticeSeverity {
+    Error,
+    Warning,
+    Notice,
+    Info,
+    Debug,
+}
+
+// 語句執行時伺服器送出的訊息，介面在訊息分頁中顯示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notice {
+    pub severity: NoticeSeverity,
+    pub code: Option<String>, // MySQL 的警告代碼；PostgreSQL 與 SQLite 無法取得
+    pub message: String,
+}
+
+// 只接收 sqlx 轉送的 PostgreSQL 通知，其他 tracing 事件一律停用
+struct NoticeCollector(Arc<Mutex<Vec<Notice>>>);
+
+struct MessageVisitor(Option<String>);
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" && self.0.is_none() {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+impl tracing::Subscriber for NoticeCollector {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.is_event() && metadata.target() == POSTGRES_NOTICE_TARGET
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = MessageVisitor(None);
+        event.record(&mut visitor);
+        let Some(message) = visitor.0 else {
+            return;
+        };
+
+        // sqlx 只保留了嚴重程度對應的 tracing 等級，INFO 與 LOG 都降為 TRACE
+        let severity = match *event.metadata().level() {
+            Level::ERROR => NoticeSeverity::Error,
+            Level::WARN => NoticeSeverity::Warning,
+            Level::INFO => NoticeSeverity::Notice,
+            Level::DEBUG => NoticeSeverity::Debug,
+            Level::TRACE => NoticeSeverity::Info,
+        };
+        if let Ok(mut notices) = self.0.lock() {
+            notices.push(Notice { severity, code: None, message });
+        }
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+// 在執行期間收集 RAISE NOTICE 等訊息；sqlx 沒有公開 SQLSTATE，因此沒有代碼
+pub(crate) async fn with_postgres_notices<F: Future>(future: F) -> (F::Output, Vec<Notice>) {
+    let notices = Arc::new(Mutex::new(Vec::new()));
+    let collector = NoticeCollector(Arc::clone(&notices));
+    let output = future.with_subscriber(collector).await;
+    let notices = notices.lock().map(|mut notices| std::mem::take(&mut *notices)).unwrap_or_default();
+    (output, notices)
+}
+
+// 必須在執行語句的同一個連接上、下一個語句之前讀取
+pub(crate) async fn mysql_warnings(conn: &mut sqlx::MySqlConnection) -> Vec<Notice> {
+    let Ok(rows) = sqlx::query("SHOW WARNINGS").fetch_all(conn).await else {
+        return vec![];
+    };
+    rows.iter()
+        .filter_map(|row| {
+            let level: String = row.try_get("Level").ok()?;
+            let severity = match level.as_str() {
+                "Error" => NoticeSeverity::Error,
+                "Warning" => NoticeSeverity::Warning,
+                _ => NoticeSeverity::Notice,
+            };
+            Some(Notice {
+                severity,
+                code: row.try_get::<u32, _>("Code").ok().map(|code| code.to_string()),
+                message: row.try_get("Message").ok()?,
+            })
+        })
+        .collect()
+}
+
+// SQLite 找不到雙引號識別字對應的欄位時會默默把它當成字串常值，
+// 以 EXPLAIN 的 String8 指令找出被當成字串的雙引號識別字
+pub(crate) async fn sqlite_notices(pool: &sqlx::SqlitePool, sql: &str) -> Vec<Notice> {
+    let (identifiers, strings) = quoted_tokens(sql);
+    if identifiers.is_empty() {
+        return vec![];
+    }
+    let Ok(program) = sqlx::query(&format!("EXPLAIN {sql}")).fetch_all(pool).await else {
+        return vec![];
+    };
+
+    let mut notices: Vec<Notice> = Vec::new();
+    for row in &program {
+        let (Ok(opcode), Ok(Some(operand))) = (row.try_get::<String, _>("opcode"), row.try_get::<Option<String>, _>("p4")) else {
+            continue;
+        };
+        // 同樣的文字也以單引號字串出現時無法區分，不回報
+        if opcode != "String8" || !identifiers.contains(&operand) || strings.contains(&operand) {
+            continue;
+        }
+        let message = format!("找不到欄位 \"{operand}\"，SQLite 已把它當成字串 '{operand}'");
+        if !notices.iter().any(|notice| notice.message == message) {
+            notices.push(Notice { severity: NoticeSeverity::Warning, code: None, message });
+        }
+    }
+    notices
+}
+
+// 語句中雙引號與單引號內的文字，略過註解
+fn quoted_tokens(sql: &str) -> (Vec<String>, Vec<String>) {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut identifiers = Vec::new();
+    let mut strings = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            '\'' | '"' => {
+                let mut text = String::new();
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == c {
+                        // 連續兩個引號是跳脫
+                        if chars.get(i + 1) == Some(&c) {
+                            text.push(c);
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    text.push(chars[i]);
+                    i += 1;
+                }
+                if c == '"' {
+                    identifiers.push(text);
+                } else {
+                    strings.push(text);
+                }
+            }
+            '-' if next == Some('-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    (identifiers, strings)
+}
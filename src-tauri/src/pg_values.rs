@@ -0,0 +1,721 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use serde_json::Value;
+use sqlx::postgres::{PgRow, PgTypeInfo, PgTypeKind, PgValueFormat};
+use sqlx::types::chrono::{DateTime, NaiveDate};
+use sqlx::{Row, TypeInfo, ValueRef};
+
+use crate::binary;
+use crate::spatial::{self, GeometryFormat};
+
+// PostgreSQL 二進位格式中日期與時間的起點 2000-01-01，相對於 Unix 時間的微秒數與西元元年起的日數
+const POSTGRES_EPOCH_MICROS: i64 = 946_684_800_000_000;
+const POSTGRES_EPOCH_DAYS_FROM_CE: i32 = 730_120;
+
+// 以下內建型別的二進位格式由本模組解碼，其他型別改以文字協定取得伺服器的文字表示
+const BINARY_OIDS: &[u32] = &[
+    16, 17, 18, 19, 20, 21, 23, 25, 26, 28, 114, 142, 650, 700, 701, 705, 774, 790, 829, 869, 1042, 1043,
+    1082, 1083, 1114, 1184, 1186, 1266, 1560, 1562, 1700, 2249, 2278, 2950, 3802,
+];
+
+// 範圍型別的旗標
+const RANGE_EMPTY: u8 = 0x01;
+const RANGE_LB_INC: u8 = 0x02;
+const RANGE_UB_INC: u8 = 0x04;
+const RANGE_LB_INF: u8 = 0x08;
+const RANGE_UB_INF: u8 = 0x10;
+
+// 依序讀取二進位格式的欄位，資料不足時返回 None
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.bytes(N)?.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.array::<1>().map(|b| b[0])
+    }
+
+    fn i16(&mut self) -> Option<i16> {
+        self.array().map(i16::from_be_bytes)
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.array().map(i32::from_be_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        self.array().map(i64::from_be_bytes)
+    }
+
+    // 以長度為前綴的值，長度為 -1 表示 NULL
+    fn value(&mut self) -> Option<Option<&'a [u8]>> {
+        let len = self.i32()?;
+        if len < 0 {
+            return Some(None);
+        }
+        self.bytes(len as usize).map(Some)
+    }
+}
+
+// 單元格轉為 JSON 值：布林、整數、浮點數與 JSON 保留型別，其餘使用 PostgreSQL 的文字表示
+pub(crate) fn postgres_value(row: &PgRow, index: usize, geometry: GeometryFormat) -> Value {
+    let Ok(raw) = row.try_get_raw(index) else {
+        return Value::Null;
+    };
+    if raw.is_null() {
+        return Value::Null;
+    }
+    let type_info = raw.type_info().into_owned();
+    match (raw.format(), raw.as_bytes()) {
+        (PgValueFormat::Binary, Ok(bytes)) => binary_value(&type_info, bytes, geometry),
+        (PgValueFormat::Text, Ok(bytes)) => text_format_value(&type_info, &utf8_or_hex(bytes), geometry),
+        (_, Err(_)) => Value::Null,
+    }
+}
+
+fn binary_value(type_info: &PgTypeInfo, bytes: &[u8], geometry: GeometryFormat) -> Value {
+    if let PgTypeKind::Domain(base) = type_info.kind() {
+        return binary_value(base, bytes, geometry);
+    }
+    // PostGIS 的 geometry 與 geography 以 EWKB 傳送
+    if matches!(type_info.name(), "geometry" | "geography") {
+        let value = match geometry {
+            GeometryFormat::GeoJson => spatial::wkb_geojson(bytes),
+            GeometryFormat::Native => Some(spatial::geometry_value(bytes, None)),
+        };
+        if let Some(value) = value {
+            return value;
+        }
+    }
+    let scalar = matches!(type_info.kind(), PgTypeKind::Simple | PgTypeKind::Pseudo);
+    let mut reader = Reader(bytes);
+    let value = match type_info.oid().map(|oid| oid.0).filter(|_| scalar) {
+        Some(16) => reader.u8().map(|b| Value::Bool(b != 0)),
+        Some(17) => Some(binary::binary_value(bytes)),
+        Some(20) => reader.i64().map(Value::from),
+        Some(21) => reader.i16().map(Value::from),
+        Some(23) => reader.i32().map(Value::from),
+        Some(26 | 28) => reader.u32().map(Value::from),
+        Some(700) => reader.array().map(f32::from_be_bytes).map(|n| float_value(f64::from(n), float_text(n))),
+        Some(701) => reader.array().map(f64::from_be_bytes).map(|n| float_value(n, float_text(n))),
+        Some(114) => serde_json::from_slice(bytes).ok(),
+        // JSONB 的第一個位元組是格式版本
+        Some(3802) => bytes.split_first().and_then(|(_, json)| serde_json::from_slice(json).ok()),
+        _ => None,
+    };
+    value.unwrap_or_else(|| Value::String(text_value(type_info, bytes)))
+}
+
+// 型別的二進位格式是否能由 binary_value 解碼；擴充型別依名稱判斷，因為 OID 在每個資料庫中不同
+pub(crate) fn decodes_binary(type_info: &PgTypeInfo) -> bool {
+    match type_info.kind() {
+        PgTypeKind::Domain(base) => decodes_binary(base),
+        // 列舉的二進位格式就是標籤文字
+        PgTypeKind::Enum(_) => true,
+        PgTypeKind::Array(element) | PgTypeKind::Range(element) => decodes_binary(element),
+        PgTypeKind::Composite(fields) => fields.iter().all(|(_, field)| decodes_binary(field)),
+        PgTypeKind::Simple | PgTypeKind::Pseudo => {
+            type_info.oid().is_some_and(|oid| BINARY_OIDS.contains(&oid.0))
+                || ["geometry", "geography", "hstore", "citext"].iter().any(|name| type_info.name().eq_ignore_ascii_case(name))
+        }
+    }
+}
+
+// 以文字協定取得的值：保留布林、數字與 JSON 的型別，bytea 與幾何先還原為位元組
+fn text_format_value(type_info: &PgTypeInfo, text: &str, geometry: GeometryFormat) -> Value {
+    if let PgTypeKind::Domain(base) = type_info.kind() {
+        return text_format_value(base, text, geometry);
+    }
+    // PostGIS 的文字表示是十六進位的 EWKB
+    if matches!(type_info.name(), "geometry" | "geography") {
+        if let Some(bytes) = from_hex(text) {
+            return binary_value(type_info, &bytes, geometry);
+        }
+    }
+    let scalar = matches!(type_info.kind(), PgTypeKind::Simple | PgTypeKind::Pseudo);
+    let value = match type_info.oid().map(|oid| oid.0).filter(|_| scalar) {
+        Some(16) => Some(Value::Bool(text == "t")),
+        Some(17) => text.strip_prefix("\\x").and_then(from_hex).map(|bytes| binary::binary_value(&bytes)),
+        Some(20 | 21 | 23 | 26 | 28) => text.parse::<i64>().ok().map(Value::from),
+        Some(700 | 701) => text.parse::<f64>().ok().map(|n| float_value(n, text.to_string())),
+        Some(114 | 3802) => serde_json::from_str(text).ok(),
+        _ => None,
+    };
+    value.unwrap_or_else(|| Value::String(text.to_string()))
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| text.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect()
+}
+
+// NaN 與無限大無法以 JSON 數字表示，保留為文字
+fn float_value(n: f64, text: String) -> Value {
+    match serde_json::Number::from_f64(n) {
+        Some(number) => Value::Number(number),
+        None => Value::String(text),
+    }
+}
+
+// PostgreSQL 的文字表示，解析失敗時以十六進位顯示原始資料
+fn text_value(type_info: &PgTypeInfo, bytes: &[u8]) -> String {
+    let text = match type_info.kind() {
+        PgTypeKind::Domain(base) => Some(text_value(base, bytes)),
+        PgTypeKind::Enum(_) => None,
+        PgTypeKind::Array(element) => array_text(element, bytes),
+        PgTypeKind::Range(element) => range_text(element, bytes),
+        PgTypeKind::Composite(fields) => {
+            let fields: Vec<&PgTypeInfo> = fields.iter().map(|(_, field)| field).collect();
+            record_text(Some(&fields), bytes)
+        }
+        PgTypeKind::Simple | PgTypeKind::Pseudo => scalar_text(type_info.oid().map(|oid| oid.0), type_info.name(), bytes),
+    };
+    text.unwrap_or_else(|| utf8_or_hex(bytes))
+}
+
+fn scalar_text(oid: Option<u32>, name: &str, bytes: &[u8]) -> Option<String> {
+    let mut reader = Reader(bytes);
+    match oid {
+        Some(16) => reader.u8().map(|b| if b != 0 { "t" } else { "f" }.to_string()),
+        Some(17) => Some(hex(bytes)),
+        Some(18) => reader.u8().map(|b| char::from(b).to_string()),
+        Some(20) => reader.i64().map(|n| n.to_string()),
+        Some(21) => reader.i16().map(|n| n.to_string()),
+        Some(23) => reader.i32().map(|n| n.to_string()),
+        Some(26 | 28) => reader.u32().map(|n| n.to_string()),
+        Some(700) => reader.array().map(f32::from_be_bytes).map(float_text),
+        Some(701) => reader.array().map(f64::from_be_bytes).map(float_text),
+        Some(3802) => bytes.split_first().and_then(|(_, json)| std::str::from_utf8(json).ok()).map(str::to_string),
+        Some(650) => inet_text(bytes, true),
+        Some(869) => inet_text(bytes, false),
+        Some(774 | 829) => Some(bytes.iter().map(|b| format!("{b:02x}")).collect::<Vec<_>>().join(":")),
+        Some(790) => reader.i64().map(|cents| decimal_text(cents, 2)),
+        Some(1082) => reader.i32().and_then(date_text),
+        Some(1083) => reader.i64().map(time_text),
+        Some(1114) => reader.i64().and_then(|micros| timestamp_text(micros, false)),
+        Some(1184) => reader.i64().and_then(|micros| timestamp_text(micros, true)),
+        Some(1186) => interval_text(&mut reader),
+        Some(1266) => timetz_text(&mut reader),
+        Some(1560 | 1562) => bit_text(&mut reader),
+        Some(1700) => numeric_text(&mut reader),
+        Some(2249) => record_text(None, bytes),
+        Some(2950) => sqlx::types::Uuid::from_slice(bytes).ok().map(|uuid| uuid.to_string()),
+        _ if name.eq_ignore_ascii_case("hstore") => hstore_text(&mut reader),
+        // 文字類型與列舉以 UTF-8 文字呈現；其他型別由 decodes_binary 改以文字協定讀取
+        _ => None,
+    }
+}
+
+fn utf8_or_hex(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => hex(bytes),
+    }
+}
+
+// bytea 的 hex 輸出格式
+fn hex(bytes: &[u8]) -> String {
+    format!("\\x{}", binary::to_hex(bytes))
+}
+
+fn float_text<F: std::fmt::Display>(n: F) -> String {
+    match n.to_string().as_str() {
+        "inf" => "Infinity".to_string(),
+        "-inf" => "-Infinity".to_string(),
+        text => text.to_string(),
+    }
+}
+
+// 以整數表示的定點數，例如 money 以分為單位
+fn decimal_text(value: i64, scale: u32) -> String {
+    let unit = 10u64.pow(scale);
+    let sign = if value < 0 { "-" } else { "" };
+    let absolute = value.unsigned_abs();
+    format!("{sign}{}.{:0width$}", absolute / unit, absolute % unit, width = scale as usize)
+}
+
+// numeric 以 10000 為底的數字儲存，weight 為第一個數字的次方
+fn numeric_text(reader: &mut Reader) -> Option<String> {
+    let ndigits = reader.i16()?;
+    let weight = reader.i16()? as i32;
+    let sign = reader.i16()? as u16;
+    let dscale = reader.i16()?.max(0) as usize;
+    let digits = (0..ndigits).map(|_| reader.i16()).collect::<Option<Vec<i16>>>()?;
+    match sign {
+        0xC000 => return Some("NaN".to_string()),
+        0xD000 => return Some("Infinity".to_string()),
+        0xF000 => return Some("-Infinity".to_string()),
+        _ => {}
+    }
+
+    let digit = |group: i32| {
+        usize::try_from(group).ok().and_then(|group| digits.get(group)).copied().unwrap_or(0)
+    };
+    let mut text = String::new();
+    if sign == 0x4000 {
+        text.push('-');
+    }
+    if weight < 0 {
+        text.push('0');
+    } else {
+        text.push_str(&digit(0).to_string());
+        for group in 1..=weight {
+            text.push_str(&format!("{:04}", digit(group)));
+        }
+    }
+    if dscale > 0 {
+        let fraction: String = (1..=dscale.div_ceil(4) as i32)
+            .map(|group| format!("{:04}", digit(weight + group)))
+            .collect();
+        text.push('.');
+        text.push_str(&fraction[..dscale]);
+    }
+    Some(text)
+}
+
+fn date_text(days: i32) -> Option<String> {
+    match days {
+        i32::MAX => Some("infinity".to_string()),
+        i32::MIN => Some("-infinity".to_string()),
+        _ => {
+            NaiveDate::from_num_days_from_ce_opt(POSTGRES_EPOCH_DAYS_FROM_CE.checked_add(days)?).map(|date| date.to_string())
+        }
+    }
+}
+
+// 時間部分，小數秒去除結尾的零
+fn time_text(micros: i64) -> String {
+    let sign = if micros < 0 { "-" } else { "" };
+    let micros = micros.unsigned_abs();
+    let seconds = micros / 1_000_000;
+    let mut text = format!("{sign}{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let fraction = micros % 1_000_000;
+    if fraction > 0 {
+        text.push_str(format!(".{fraction:06}").trim_end_matches('0'));
+    }
+    text
+}
+
+fn timestamp_text(micros: i64, with_time_zone: bool) -> Option<String> {
+    match micros {
+        i64::MAX => Some("infinity".to_string()),
+        i64::MIN => Some("-infinity".to_string()),
+        _ => {
+            let timestamp = DateTime::from_timestamp_micros(micros.checked_add(POSTGRES_EPOCH_MICROS)?)?;
+            Some(if with_time_zone {
+                timestamp.to_rfc3339()
+            } else {
+                timestamp.naive_utc().to_string()
+            })
+        }
+    }
+}
+
+// 時區以 UTC 以西的秒數儲存
+fn timetz_text(reader: &mut Reader) -> Option<String> {
+    let time = time_text(reader.i64()?);
+    let zone = reader.i32()?;
+    let sign = if zone <= 0 { '+' } else { '-' };
+    let offset = zone.unsigned_abs();
+    let mut text = format!("{time}{sign}{:02}", offset / 3600);
+    if offset % 3600 != 0 {
+        text.push_str(&format!(":{:02}", offset / 60 % 60));
+    }
+    Some(text)
+}
+
+// 與 PostgreSQL 預設的 IntervalStyle (postgres) 相同，例如 1 year 2 mons 3 days 04:05:06
+fn interval_text(reader: &mut Reader) -> Option<String> {
+    let micros = reader.i64()?;
+    let days = reader.i32()?;
+    let months = reader.i32()?;
+
+    let mut parts = Vec::new();
+    let unit = |count: i32, singular: &str, plural: &str| {
+        format!("{count} {}", if count.abs() == 1 { singular } else { plural })
+    };
+    if months / 12 != 0 {
+        parts.push(unit(months / 12, "year", "years"));
+    }
+    if months % 12 != 0 {
+        parts.push(unit(months % 12, "mon", "mons"));
+    }
+    if days != 0 {
+        parts.push(unit(days, "day", "days"));
+    }
+    if micros != 0 || parts.is_empty() {
+        parts.push(time_text(micros));
+    }
+    Some(parts.join(" "))
+}
+
+// inet 在遮罩為完整長度時省略，cidr 一律顯示遮罩
+fn inet_text(bytes: &[u8], is_cidr: bool) -> Option<String> {
+    let mut reader = Reader(bytes);
+    let family = reader.u8()?;
+    let bits = reader.u8()?;
+    let _is_cidr = reader.u8()?;
+    let length = reader.u8()?;
+    let (address, max_bits) = match (family, length) {
+        (2, 4) => (Ipv4Addr::from(reader.array::<4>()?).to_string(), 32),
+        (3, 16) => (Ipv6Addr::from(reader.array::<16>()?).to_string(), 128),
+        _ => return None,
+    };
+    Some(if is_cidr || bits != max_bits {
+        format!("{address}/{bits}")
+    } else {
+        address
+    })
+}
+
+fn bit_text(reader: &mut Reader) -> Option<String> {
+    let length = usize::try_from(reader.i32()?).ok()?;
+    let bytes = reader.bytes(length.div_ceil(8))?;
+    Some((0..length).map(|i| if bytes[i / 8] & (0x80 >> (i % 8)) != 0 { '1' } else { '0' }).collect())
+}
+
+fn hstore_text(reader: &mut Reader) -> Option<String> {
+    let count = reader.i32()?;
+    let mut pairs = Vec::new();
+    for _ in 0..count {
+        let key = reader.value()??;
+        let value = match reader.value()? {
+            Some(value) => quote_element(&utf8_or_hex(value), true),
+            None => "NULL".to_string(),
+        };
+        pairs.push(format!("{}=>{value}", quote_element(&utf8_or_hex(key), true)));
+    }
+    Some(pairs.join(", "))
+}
+
+// 陣列、範圍與複合型別中的元素需要時加上雙引號
+fn quote_element(text: &str, always: bool) -> String {
+    let needs_quotes = always
+        || text.is_empty()
+        || text.eq_ignore_ascii_case("null")
+        || text.chars().any(|c| c.is_whitespace() || "{}()[],\"\\".contains(c));
+    if needs_quotes {
+        let escaped = text.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("\"{escaped}\"")
+    } else {
+        text.to_string()
+    }
+}
+
+// 陣列的文字表示，例如 {1,2,NULL} 或 {{a,b},{c,d}}
+fn array_text(element: &PgTypeInfo, bytes: &[u8]) -> Option<String> {
+    let mut reader = Reader(bytes);
+    let dimensions = usize::try_from(reader.i32()?).ok()?;
+    let _has_nulls = reader.i32()?;
+    let _element_oid = reader.u32()?;
+    let mut lengths = Vec::with_capacity(dimensions);
+    let mut bounds = Vec::with_capacity(dimensions);
+    for _ in 0..dimensions {
+        lengths.push(usize::try_from(reader.i32()?).ok()?);
+        bounds.push(reader.i32()?);
+    }
+    if dimensions == 0 {
+        return Some("{}".to_string());
+    }
+
+    let body = array_dimension(element, &mut reader, &lengths)?;
+    // 下界不是 1 時 PostgreSQL 會在前面標示範圍，例如 [0:2]={1,2,3}
+    if bounds.iter().all(|&bound| bound == 1) {
+        Some(body)
+    } else {
+        let ranges: String = bounds
+            .iter()
+            .zip(&lengths)
+            .map(|(&bound, &length)| format!("[{bound}:{}]", i64::from(bound) + length as i64 - 1))
+            .collect();
+        Some(format!("{ranges}={body}"))
+    }
+}
+
+fn array_dimension(element: &PgTypeInfo, reader: &mut Reader, lengths: &[usize]) -> Option<String> {
+    let (&length, inner) = lengths.split_first()?;
+    let mut items = Vec::with_capacity(length);
+    for _ in 0..length {
+        let item = if inner.is_empty() {
+            match reader.value()? {
+                Some(bytes) => quote_element(&text_value(element, bytes), false),
+                None => "NULL".to_string(),
+            }
+        } else {
+            array_dimension(element, reader, inner)?
+        };
+        items.push(item);
+    }
+    Some(format!("{{{}}}", items.join(",")))
+}
+
+// 範圍的文字表示，例如 [1,10) 或 empty
+fn range_text(element: &PgTypeInfo, bytes: &[u8]) -> Option<String> {
+    let mut reader = Reader(bytes);
+    let flags = reader.u8()?;
+    if flags & RANGE_EMPTY != 0 {
+        return Some("empty".to_string());
+    }
+
+    let mut bound = |infinite: bool| -> Option<String> {
+        if infinite {
+            return Some(String::new());
+        }
+        let bytes = reader.value()??;
+        Some(quote_element(&text_value(element, bytes), false))
+    };
+    let lower = bound(flags & RANGE_LB_INF != 0)?;
+    let upper = bound(flags & RANGE_UB_INF != 0)?;
+    let open = if flags & RANGE_LB_INC != 0 { '[' } else { '(' };
+    let close = if flags & RANGE_UB_INC != 0 { ']' } else { ')' };
+    Some(format!("{open}{lower},{upper}{close}"))
+}
+
+// 複合型別的文字表示，例如 (1,abc,)；匿名 record 依各欄位的 OID 解碼
+fn record_text(fields: Option<&[&PgTypeInfo]>, bytes: &[u8]) -> Option<String> {
+    let mut reader = Reader(bytes);
+    let count = usize::try_from(reader.i32()?).ok()?;
+    let mut items = Vec::with_capacity(count);
+    for i in 0..count {
+        let oid = reader.u32()?;
+        let item = match reader.value()? {
+            Some(bytes) => {
+                let text = match fields.and_then(|fields| fields.get(i)) {
+                    Some(field) => text_value(field, bytes),
+                    None => scalar_text(Some(oid), "", bytes).unwrap_or_else(|| utf8_or_hex(bytes)),
+                };
+                quote_element(&text, false)
+            }
+            None => String::new(),
+        };
+        items.push(item);
+    }
+    Some(format!("({})", items.join(",")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sqlx::postgres::types::PgRange;
+    use sqlx::{Postgres, Type};
+
+    fn type_info<T: Type<Postgres>>() -> PgTypeInfo {
+        T::type_info()
+    }
+
+    fn text(oid: u32, bytes: &[u8]) -> Option<String> {
+        scalar_text(Some(oid), "", bytes)
+    }
+
+    // 以長度為前綴的值
+    fn field(bytes: &[u8]) -> Vec<u8> {
+        let mut out = (bytes.len() as i32).to_be_bytes().to_vec();
+        out.extend(bytes);
+        out
+    }
+
+    fn numeric(weight: i16, sign: u16, dscale: i16, digits: &[i16]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for value in [digits.len() as i16, weight, sign as i16, dscale] {
+            out.extend(value.to_be_bytes());
+        }
+        for digit in digits {
+            out.extend(digit.to_be_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn typed_binary_values() {
+        let cases: Vec<(PgTypeInfo, Vec<u8>, Value)> = vec![
+            (type_info::<bool>(), vec![1], json!(true)),
+            (type_info::<i16>(), (-5i16).to_be_bytes().to_vec(), json!(-5)),
+            (type_info::<i32>(), 42i32.to_be_bytes().to_vec(), json!(42)),
+            (type_info::<i64>(), (1i64 << 40).to_be_bytes().to_vec(), json!(1i64 << 40)),
+            (type_info::<sqlx::postgres::types::Oid>(), 3000u32.to_be_bytes().to_vec(), json!(3000)),
+            (type_info::<f32>(), 1.5f32.to_be_bytes().to_vec(), json!(1.5)),
+            (type_info::<f64>(), f64::NAN.to_be_bytes().to_vec(), json!("NaN")),
+            (type_info::<f64>(), f64::NEG_INFINITY.to_be_bytes().to_vec(), json!("-Infinity")),
+            (type_info::<sqlx::types::Json<Value>>(), [&[1u8][..], br#"{"a":1}"#].concat(), json!({ "a": 1 })),
+            (type_info::<String>(), "文字".as_bytes().to_vec(), json!("文字")),
+        ];
+        for (type_info, bytes, expected) in cases {
+            assert_eq!(binary_value(&type_info, &bytes, GeometryFormat::GeoJson), expected, "{}", type_info.name());
+        }
+
+        let bytea = binary_value(&type_info::<Vec<u8>>(), &[1, 2], GeometryFormat::GeoJson);
+        assert_eq!(bytea["length"], json!(2));
+    }
+
+    #[test]
+    fn scalar_text_cases() {
+        assert_eq!(text(16, &[0]).as_deref(), Some("f"));
+        assert_eq!(text(17, &[0xde, 0xad]).as_deref(), Some("\\xdead"));
+        assert_eq!(text(18, b"a").as_deref(), Some("a"));
+        assert_eq!(text(20, &(-7i64).to_be_bytes()).as_deref(), Some("-7"));
+        assert_eq!(text(700, &f32::INFINITY.to_be_bytes()).as_deref(), Some("Infinity"));
+        assert_eq!(text(3802, &[&[1u8][..], b"[1]"].concat()).as_deref(), Some("[1]"));
+        assert_eq!(text(790, &(-1234i64).to_be_bytes()).as_deref(), Some("-12.34"));
+        assert_eq!(text(774, &[8, 0, 0x2b, 1, 2, 3, 4, 5]).as_deref(), Some("08:00:2b:01:02:03:04:05"));
+        assert_eq!(text(829, &[8, 0, 0x2b, 1, 2, 3]).as_deref(), Some("08:00:2b:01:02:03"));
+        assert_eq!(text(2950, &[0x11; 16]).as_deref(), Some("11111111-1111-1111-1111-111111111111"));
+        // 沒有解碼器的型別交給文字協定
+        assert_eq!(text(600, &[0; 16]), None);
+        assert_eq!(text(3614, &[0; 4]), None);
+    }
+
+    #[test]
+    fn inet_and_cidr() {
+        assert_eq!(text(869, &[2, 32, 0, 4, 192, 168, 0, 1]).as_deref(), Some("192.168.0.1"));
+        assert_eq!(text(869, &[2, 24, 0, 4, 192, 168, 0, 1]).as_deref(), Some("192.168.0.1/24"));
+        assert_eq!(text(650, &[2, 32, 1, 4, 10, 0, 0, 1]).as_deref(), Some("10.0.0.1/32"));
+        let mut ipv6 = vec![3, 128, 0, 16];
+        ipv6.extend([0; 15]);
+        ipv6.push(1);
+        assert_eq!(text(869, &ipv6).as_deref(), Some("::1"));
+        assert_eq!(text(869, &[9, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn date_and_time() {
+        assert_eq!(text(1082, &0i32.to_be_bytes()).as_deref(), Some("2000-01-01"));
+        assert_eq!(text(1082, &(-1i32).to_be_bytes()).as_deref(), Some("1999-12-31"));
+        assert_eq!(text(1082, &i32::MAX.to_be_bytes()).as_deref(), Some("infinity"));
+        assert_eq!(text(1083, &3_723_500_000i64.to_be_bytes()).as_deref(), Some("01:02:03.5"));
+        assert_eq!(text(1114, &0i64.to_be_bytes()).as_deref(), Some("2000-01-01 00:00:00"));
+        assert_eq!(text(1114, &i64::MIN.to_be_bytes()).as_deref(), Some("-infinity"));
+        assert_eq!(text(1184, &1_000_000i64.to_be_bytes()).as_deref(), Some("2000-01-01T00:00:01+00:00"));
+
+        // 10:00:00 位於 UTC+5:30，時區以 UTC 以西的秒數儲存
+        let timetz = [36_000_000_000i64.to_be_bytes().to_vec(), (-19_800i32).to_be_bytes().to_vec()].concat();
+        assert_eq!(text(1266, &timetz).as_deref(), Some("10:00:00+05:30"));
+        let timetz = [0i64.to_be_bytes().to_vec(), 18_000i32.to_be_bytes().to_vec()].concat();
+        assert_eq!(text(1266, &timetz).as_deref(), Some("00:00:00-05"));
+    }
+
+    #[test]
+    fn interval_cases() {
+        let interval = |micros: i64, days: i32, months: i32| {
+            [micros.to_be_bytes().to_vec(), days.to_be_bytes().to_vec(), months.to_be_bytes().to_vec()].concat()
+        };
+        assert_eq!(text(1186, &interval(14_706_000_000, 3, 14)).as_deref(), Some("1 year 2 mons 3 days 04:05:06"));
+        assert_eq!(text(1186, &interval(0, 1, 0)).as_deref(), Some("1 day"));
+        assert_eq!(text(1186, &interval(-1_500_000, 0, 0)).as_deref(), Some("-00:00:01.5"));
+        assert_eq!(text(1186, &interval(0, 0, 0)).as_deref(), Some("00:00:00"));
+    }
+
+    #[test]
+    fn numeric_cases() {
+        assert_eq!(text(1700, &numeric(1, 0, 3, &[1, 2345, 6780])).as_deref(), Some("12345.678"));
+        assert_eq!(text(1700, &numeric(-1, 0x4000, 2, &[500])).as_deref(), Some("-0.05"));
+        assert_eq!(text(1700, &numeric(2, 0, 0, &[1])).as_deref(), Some("100000000"));
+        assert_eq!(text(1700, &numeric(0, 0, 0, &[])).as_deref(), Some("0"));
+        assert_eq!(text(1700, &numeric(0, 0xC000, 0, &[])).as_deref(), Some("NaN"));
+        assert_eq!(text(1700, &numeric(0, 0xF000, 0, &[])).as_deref(), Some("-Infinity"));
+    }
+
+    #[test]
+    fn bit_and_hstore() {
+        let bits = [5i32.to_be_bytes().to_vec(), vec![0b1010_0000]].concat();
+        assert_eq!(text(1560, &bits).as_deref(), Some("10100"));
+
+        let mut hstore = 2i32.to_be_bytes().to_vec();
+        hstore.extend(field(b"a"));
+        hstore.extend(field(b"x y"));
+        hstore.extend(field(b"b"));
+        hstore.extend((-1i32).to_be_bytes());
+        assert_eq!(scalar_text(None, "hstore", &hstore).as_deref(), Some(r#""a"=>"x y", "b"=>NULL"#));
+    }
+
+    #[test]
+    fn record_cases() {
+        let mut record = 3i32.to_be_bytes().to_vec();
+        record.extend(23u32.to_be_bytes());
+        record.extend(field(&7i32.to_be_bytes()));
+        record.extend(25u32.to_be_bytes());
+        record.extend(field(b"a b"));
+        record.extend(25u32.to_be_bytes());
+        record.extend((-1i32).to_be_bytes());
+        assert_eq!(text(2249, &record).as_deref(), Some(r#"(7,"a b",)"#));
+    }
+
+    #[test]
+    fn array_cases() {
+        let array = |lower: i32, items: &[Option<i32>]| {
+            let mut out = [1i32, 1, 23].iter().flat_map(|n| n.to_be_bytes()).collect::<Vec<u8>>();
+            out.extend((items.len() as i32).to_be_bytes());
+            out.extend(lower.to_be_bytes());
+            for item in items {
+                match item {
+                    Some(n) => out.extend(field(&n.to_be_bytes())),
+                    None => out.extend((-1i32).to_be_bytes()),
+                }
+            }
+            out
+        };
+        let int_array = type_info::<Vec<i32>>();
+        let value = |bytes: &[u8]| binary_value(&int_array, bytes, GeometryFormat::GeoJson);
+        assert_eq!(value(&array(1, &[Some(1), None, Some(3)])), json!("{1,NULL,3}"));
+        assert_eq!(value(&array(0, &[Some(1), Some(2)])), json!("[0:1]={1,2}"));
+        assert_eq!(value(&[0i32, 0, 23].iter().flat_map(|n| n.to_be_bytes()).collect::<Vec<u8>>()), json!("{}"));
+
+        let mut text_array = [1i32, 0, 25, 1, 1].iter().flat_map(|n| n.to_be_bytes()).collect::<Vec<u8>>();
+        text_array.extend(field(b"a,b"));
+        assert_eq!(binary_value(&type_info::<Vec<String>>(), &text_array, GeometryFormat::GeoJson), json!(r#"{"a,b"}"#));
+    }
+
+    #[test]
+    fn range_cases() {
+        let range = type_info::<PgRange<i32>>();
+        let mut bytes = vec![RANGE_LB_INC];
+        bytes.extend(field(&1i32.to_be_bytes()));
+        bytes.extend(field(&10i32.to_be_bytes()));
+        assert_eq!(binary_value(&range, &bytes, GeometryFormat::GeoJson), json!("[1,10)"));
+
+        let mut bytes = vec![RANGE_UB_INF];
+        bytes.extend(field(&5i32.to_be_bytes()));
+        assert_eq!(binary_value(&range, &bytes, GeometryFormat::GeoJson), json!("(5,)"));
+        assert_eq!(binary_value(&range, &[RANGE_EMPTY], GeometryFormat::GeoJson), json!("empty"));
+    }
+
+    #[test]
+    fn text_format_values() {
+        let value = |type_info: PgTypeInfo, text: &str| text_format_value(&type_info, text, GeometryFormat::GeoJson);
+        assert_eq!(value(type_info::<bool>(), "t"), json!(true));
+        assert_eq!(value(type_info::<i64>(), "-42"), json!(-42));
+        assert_eq!(value(type_info::<f64>(), "Infinity"), json!("Infinity"));
+        assert_eq!(value(type_info::<sqlx::types::Json<Value>>(), r#"{"a": [1]}"#), json!({ "a": [1] }));
+        assert_eq!(value(type_info::<Vec<u8>>(), "\\x0102")["length"], json!(2));
+        assert_eq!(value(type_info::<String>(), "(1,2)"), json!("(1,2)"));
+    }
+
+    #[test]
+    fn binary_decoding_support() {
+        assert!(decodes_binary(&type_info::<i32>()));
+        assert!(decodes_binary(&type_info::<Vec<String>>()));
+        assert!(decodes_binary(&type_info::<PgRange<i64>>()));
+        assert!(decodes_binary(&type_info::<sqlx::postgres::types::PgInterval>()));
+        assert!(decodes_binary(&type_info::<Vec<sqlx::postgres::types::PgInterval>>()));
+    }
+}
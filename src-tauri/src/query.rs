@@ -0,0 +1,421 @@
+use std::time::{Duration, Instant};
+
+use futures_util::TryStreamExt;
+use serde_json::Value;
+use sqlx::{Column, Either, Executor, Row, TypeInfo};
+
+use crate::binary::truncate_binary;
+use crate::connection::{connect_mysql, connect_postgres, connect_sqlite};
+use crate::pg_values;
+use crate::sql_guard::main_keyword;
+use crate::sql_splitter::split_statements;
+use crate::spatial::GeometryFormat;
+use crate::values::{
+    mysql_export_values, mysql_row_values, postgres_export_values, postgres_row_values, sqlite_export_values, sqlite_row_values,
+};
+use crate::DatabaseConnection;
+
+#[derive(Debug, Clone)]
+pub(crate) struct ResultColumn {
+    pub name: String,
+    pub type_name: String,
+}
+
+// 逐行接收查詢結果，避免整個結果集留在記憶體中
+// 每個結果集開始時都會呼叫一次 on_columns
+pub(crate) trait RowHandler: Send {
+    fn on_columns(&mut self, columns: &[ResultColumn]) -> Result<(), String>;
+    fn on_row(&mut self, values: Vec<Value>) -> Result<(), String>;
+
+    // 返回 true 時停止讀取目前結果集的其餘資料列
+    fn is_full(&self) -> bool {
+        false
+    }
+
+    // 每個結果集讀取完畢後呼叫一次
+    fn on_timing(&mut self, _timing: FetchTiming) {}
+}
+
+// 讀取一個結果集的時間分配
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct FetchTiming {
+    pub first_row: Duration, // 送出查詢到收到第一行（或得知沒有資料）
+    pub fetch: Duration, // 等待其餘資料列，不含解碼
+    pub decode: Duration, // 把資料列解碼為 JSON 值
+}
+
+// 要收集的資料列範圍：略過前 offset 行，最多保留 limit 行
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RowWindow {
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+// 將結果收集到記憶體，供 execute_query 返回給介面
+#[derive(Debug, Default)]
+pub(crate) struct CollectedRows {
+    pub columns: Vec<ResultColumn>,
+    pub rows: Vec<Vec<Value>>,
+    pub window: RowWindow,
+    pub skipped: usize,
+    pub truncated: bool, // 超過 limit 的資料列存在但未讀取
+    pub timing: FetchTiming,
+}
+
+impl CollectedRows {
+    pub(crate) fn with_window(window: RowWindow) -> Self {
+        Self { window, ..Self::default() }
+    }
+}
+
+impl RowHandler for CollectedRows {
+    fn on_columns(&mut self, columns: &[ResultColumn]) -> Result<(), String> {
+        self.columns = columns.to_vec();
+        Ok(())
+    }
+
+    fn on_row(&mut self, mut values: Vec<Value>) -> Result<(), String> {
+        if self.skipped < self.window.offset {
+            self.skipped += 1;
+        } else if self.window.limit.is_some_and(|limit| self.rows.len() >= limit) {
+            // 多讀到一行才能確定結果確實被截斷
+            self.truncated = true;
+        } else {
+            values.iter_mut().for_each(truncate_binary);
+            self.rows.push(values);
+        }
+        Ok(())
+    }
+
+    fn is_full(&self) -> bool {
+        self.truncated
+    }
+
+    fn on_timing(&mut self, timing: FetchTiming) {
+        self.timing.first_row += timing.first_row;
+        self.timing.fetch += timing.fetch;
+        self.timing.decode += timing.decode;
+    }
+}
+
+// 計算序列化後的位元組數，不保留內容
+#[derive(Default)]
+pub(crate) struct ByteCounter(pub u64);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn result_columns<C: Column>(columns: &[C]) -> Vec<ResultColumn> {
+    columns
+        .iter()
+        .map(|column| ResultColumn {
+            name: column.name().to_string(),
+            type_name: column.type_info().name().to_string(),
+        })
+        .collect()
+}
+
+// 結果以二進位格式傳回，有無法解碼的型別時改以文字協定執行，由伺服器輸出型別的文字表示
+pub(crate) trait ResultFormat: sqlx::Database {
+    // 執行前是否需要以 describe 檢查欄位型別
+    const CHECKS_TYPES: bool = false;
+
+    fn decodes_binary(_type_info: &Self::TypeInfo) -> bool {
+        true
+    }
+}
+
+impl ResultFormat for sqlx::Sqlite {}
+
+impl ResultFormat for sqlx::MySql {}
+
+impl ResultFormat for sqlx::Postgres {
+    const CHECKS_TYPES: bool = true;
+
+    fn decodes_binary(type_info: &sqlx::postgres::PgTypeInfo) -> bool {
+        pg_values::decodes_binary(type_info)
+    }
+}
+
+async fn needs_text_format<DB>(conn: &mut DB::Connection, sql: &str) -> bool
+where
+    DB: ResultFormat,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    if !DB::CHECKS_TYPES {
+        return false;
+    }
+    match conn.describe(sql).await {
+        Ok(describe) => describe.columns().iter().any(|column| !DB::decodes_binary(column.type_info())),
+        Err(_) => false,
+    }
+}
+
+// 以串流方式執行查詢，返回處理的行數
+pub(crate) async fn stream_query(
+    connection: &DatabaseConnection,
+    sql: &str,
+    geometry: GeometryFormat,
+    handler: &mut dyn RowHandler,
+) -> Result<u64, String> {
+    stream_queries(connection, &[sql.to_string()], geometry, handler).await
+}
+
+// 在同一個連接池依序執行多條語句，每條語句各自形成一個結果集
+// 要寫回資料庫的值（SQL INSERT 匯出）以 GeometryFormat::Native 保留幾何的原始格式
+pub(crate) async fn stream_queries(
+    connection: &DatabaseConnection,
+    statements: &[String],
+    geometry: GeometryFormat,
+    handler: &mut dyn RowHandler,
+) -> Result<u64, String> {
+    let native = geometry == GeometryFormat::Native;
+    match connection.db_type.as_str() {
+        "sqlite" => {
+            let pool = connect_sqlite(connection).await?;
+            let decode = if native { sqlite_export_values } else { sqlite_row_values };
+            let result = stream_statements(&pool, statements, decode, handler).await;
+            pool.close().await;
+            result
+        }
+        "mysql" => {
+            let pool = connect_mysql(connection).await?;
+            let decode = if native { mysql_export_values } else { mysql_row_values };
+            let result = stream_statements(&pool, statements, decode, handler).await;
+            pool.close().await;
+            result
+        }
+        "postgresql" => {
+            let pool = connect_postgres(connection).await?;
+            let decode = if native { postgres_export_values } else { postgres_row_values };
+            let result = stream_statements(&pool, statements, decode, handler).await;
+            pool.close().await;
+            result
+        }
+        _ => Err("不支援的資料庫類型".to_string()),
+    }
+}
+
+async fn stream_statements<DB>(
+    pool: &sqlx::Pool<DB>,
+    statements: &[String],
+    decode: fn(&DB::Row) -> Vec<Value>,
+    handler: &mut dyn RowHandler,
+) -> Result<u64, String>
+where
+    DB: ResultFormat,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: sqlx::IntoArguments<'q, DB>,
+{
+    let mut row_count = 0u64;
+    for sql in statements {
+        row_count += stream_rows(pool, sql, decode, handler).await?;
+    }
+    Ok(row_count)
+}
+
+pub(crate) async fn stream_rows<DB>(
+    pool: &sqlx::Pool<DB>,
+    sql: &str,
+    decode: fn(&DB::Row) -> Vec<Value>,
+    handler: &mut dyn RowHandler,
+) -> Result<u64, String>
+where
+    DB: ResultFormat,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: sqlx::IntoArguments<'q, DB>,
+{
+    let mut conn = pool.acquire().await.map_err(|e| format!("查詢執行錯誤: {e}"))?;
+    stream_rows_on(&mut conn, sql, decode, handler).await
+}
+
+// 在指定的連接上執行，讓呼叫者可以在同一個連接上讀取語句的警告
+pub(crate) async fn stream_rows_on<DB>(
+    conn: &mut DB::Connection,
+    sql: &str,
+    decode: fn(&DB::Row) -> Vec<Value>,
+    handler: &mut dyn RowHandler,
+) -> Result<u64, String>
+where
+    DB: ResultFormat,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: sqlx::IntoArguments<'q, DB>,
+{
+    let text_format = needs_text_format::<DB>(conn, sql).await;
+    let started = Instant::now();
+    let mut timing = FetchTiming::default();
+    let mut rows = match text_format {
+        true => sqlx::raw_sql(sql).fetch(&mut *conn),
+        false => sqlx::query::<DB>(sql).fetch(&mut *conn),
+    };
+    let mut row_count = 0u64;
+
+    loop {
+        let waiting = Instant::now();
+        let next = rows.try_next().await.map_err(|e| format!("查詢執行錯誤: {e}"))?;
+        // 第一行之前的等待是伺服器執行時間，之後才是讀取時間
+        if row_count == 0 {
+            timing.first_row = started.elapsed();
+        } else {
+            timing.fetch += waiting.elapsed();
+        }
+        let Some(row) = next else {
+            break;
+        };
+
+        if row_count == 0 {
+            handler.on_columns(&result_columns(row.columns()))?;
+        }
+        let decoding = Instant::now();
+        let values = decode(&row);
+        timing.decode += decoding.elapsed();
+        handler.on_row(values)?;
+        row_count += 1;
+        if handler.is_full() {
+            break;
+        }
+    }
+    drop(rows);
+    handler.on_timing(timing);
+
+    // 沒有任何資料時仍需要欄位資訊（例如匯出標題列）
+    if row_count == 0 {
+        let columns = match conn.describe(sql).await {
+            Ok(describe) => result_columns(describe.columns()),
+            Err(_) => vec![],
+        };
+        handler.on_columns(&columns)?;
+    }
+
+    Ok(row_count)
+}
+
+// fetch_many 串流中的一個結果集或更新計數
+#[derive(Debug, Default)]
+pub(crate) struct StatementResult {
+    pub collected: CollectedRows,
+    pub rows_affected: u64,
+}
+
+// 多條語句的批次與預存程序呼叫可能返回多個結果集
+pub(crate) fn returns_many_results(sql: &str, db_type: &str) -> bool {
+    main_keyword(sql, db_type) == "CALL" || split_statements(sql, db_type).len() > 1
+}
+
+// 以 fetch_many 依伺服器返回的順序讀取所有結果集，每個結果集各自套用 window
+// 以文字協定送出，才能在一次請求中執行多條語句與取得預存程序的多個結果集；沒有資料列的結果集無法取得欄位
+pub(crate) async fn stream_result_sets<DB>(
+    conn: &mut DB::Connection,
+    sql: &str,
+    decode: fn(&DB::Row) -> Vec<Value>,
+    rows_affected: fn(&DB::QueryResult) -> u64,
+    window: RowWindow,
+) -> Result<Vec<StatementResult>, String>
+where
+    DB: sqlx::Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    let stream = sqlx::raw_sql(sql).fetch_many(&mut *conn);
+    collect_result_sets::<DB>(stream, decode, rows_affected, window).await
+}
+
+// 以擴充協定執行單一語句，值以二進位格式傳回，解碼方式與單一查詢相同
+pub(crate) async fn stream_statement_result<DB>(
+    conn: &mut DB::Connection,
+    sql: &str,
+    decode: fn(&DB::Row) -> Vec<Value>,
+    rows_affected: fn(&DB::QueryResult) -> u64,
+    window: RowWindow,
+) -> Result<StatementResult, String>
+where
+    DB: ResultFormat,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: sqlx::IntoArguments<'q, DB>,
+{
+    let stream = match needs_text_format::<DB>(conn, sql).await {
+        true => sqlx::raw_sql(sql).fetch_many(&mut *conn),
+        false => (&mut *conn).fetch_many(sqlx::query::<DB>(sql)),
+    };
+    let results = collect_result_sets::<DB>(stream, decode, rows_affected, window).await?;
+    let mut result = results
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| StatementResult { collected: CollectedRows::with_window(window), rows_affected: 0 });
+
+    // 沒有資料列的查詢仍需要欄位，讓介面顯示空的結果集
+    if result.collected.columns.is_empty() && result.rows_affected == 0 {
+        if let Ok(describe) = conn.describe(sql).await {
+            let columns = result_columns(describe.columns());
+            if !columns.is_empty() {
+                result.collected.on_columns(&columns)?;
+            }
+        }
+    }
+    Ok(result)
+}
+
+type ResultStream<'e, DB> = futures_util::stream::BoxStream<
+    'e,
+    Result<Either<<DB as sqlx::Database>::QueryResult, <DB as sqlx::Database>::Row>, sqlx::Error>,
+>;
+
+async fn collect_result_sets<DB: sqlx::Database>(
+    mut stream: ResultStream<'_, DB>,
+    decode: fn(&DB::Row) -> Vec<Value>,
+    rows_affected: fn(&DB::QueryResult) -> u64,
+    window: RowWindow,
+) -> Result<Vec<StatementResult>, String> {
+    let mut results = Vec::new();
+    let mut current = StatementResult { collected: CollectedRows::with_window(window), rows_affected: 0 };
+    let mut row_count = 0usize;
+
+    loop {
+        let waiting = Instant::now();
+        let next = stream.try_next().await.map_err(|e| format!("查詢執行錯誤: {e}"))?;
+        if row_count == 0 {
+            current.collected.timing.first_row += waiting.elapsed();
+        } else {
+            current.collected.timing.fetch += waiting.elapsed();
+        }
+
+        match next {
+            None => break,
+            // 每條語句結束時返回影響的行數，之後的資料列屬於下一個結果集
+            Some(Either::Left(done)) => {
+                let next = StatementResult { collected: CollectedRows::with_window(window), rows_affected: 0 };
+                let mut finished = std::mem::replace(&mut current, next);
+                finished.rows_affected = rows_affected(&done);
+                results.push(finished);
+                row_count = 0;
+            }
+            Some(Either::Right(row)) => {
+                if row_count == 0 {
+                    current.collected.on_columns(&result_columns(row.columns()))?;
+                }
+                row_count += 1;
+                // 已截斷時仍需讀完串流才能取得後面的結果集，只略過解碼
+                if current.collected.is_full() {
+                    continue;
+                }
+                let decoding = Instant::now();
+                let values = decode(&row);
+                current.collected.timing.decode += decoding.elapsed();
+                current.collected.on_row(values)?;
+            }
+        }
+    }
+
+    // 串流結束前沒有收到影響行數的資料列仍保留
+    if row_count > 0 {
+        results.push(current);
+    }
+    Ok(results)
+}
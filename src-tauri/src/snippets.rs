@@ -0,0 +1,648 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::app_db::{now_millis, AppDatabase};
+
+// 匯出的 JSON 格式版本
+const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snippet {
+    pub id: i64,
+    pub name: String,
+    pub folder: String, // 以 '/' 分隔的資料夾路徑，空字串為根目錄
+    pub description: String,
+    pub sql: String,
+    pub tags: Vec<String>,
+    pub default_connection_id: Option<String>,
+    pub created_at: i64, // Unix 時間（毫秒）
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveSnippetRequest {
+    pub id: Option<i64>, // 未指定時新增片段
+    pub name: String,
+    #[serde(default)]
+    pub folder: String,
+    #[serde(default)]
+    pub description: String,
+    pub sql: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub default_connection_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchSnippetsRequest {
+    pub text: Option<String>, // 全文搜尋名稱、說明、SQL 與標籤
+    pub folder: Option<String>, // 包含子資料夾
+    pub tag: Option<String>,
+    pub connection_id: Option<String>,
+    pub limit: u32,
+}
+
+impl Default for SearchSnippetsRequest {
+    fn default() -> Self {
+        Self {
+            text: None,
+            folder: None,
+            tag: None,
+            connection_id: None,
+            limit: 500,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchSnippetsResult {
+    pub success: bool,
+    pub snippets: Vec<Snippet>,
+    pub folders: Vec<String>, // 所有片段使用的資料夾，供介面建立樹狀結構
+    pub tags: Vec<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportSnippetsRequest {
+    pub format: String, // 'json', 'directory'
+    pub path: Option<String>, // 未指定時開啟對話框
+    #[serde(default)]
+    pub ids: Vec<i64>, // 未指定時匯出所有片段
+    #[serde(default)]
+    pub folder: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportSnippetsRequest {
+    pub format: String, // 'json', 'directory'
+    pub path: Option<String>, // 未指定時開啟對話框
+    #[serde(default)]
+    pub overwrite: bool, // 同一資料夾已有同名片段時覆寫，否則略過
+    #[serde(default)]
+    pub folder: Option<String>, // 匯入到指定資料夾之下
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnippetTransferResult {
+    pub success: bool,
+    pub path: Option<String>,
+    pub created: u64,
+    pub updated: u64,
+    pub skipped: u64,
+    pub message: String,
+}
+
+impl SnippetTransferResult {
+    fn cancelled() -> Self {
+        Self {
+            success: false,
+            path: None,
+            created: 0,
+            updated: 0,
+            skipped: 0,
+            message: "已取消操作".to_string(),
+        }
+    }
+}
+
+// 匯出檔案中的片段，不包含只在本機有意義的 id 與時間
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BundledSnippet {
+    name: String,
+    #[serde(default)]
+    folder: String,
+    #[serde(default)]
+    description: String,
+    sql: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    default_connection_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SnippetBundle {
+    version: u32,
+    snippets: Vec<BundledSnippet>,
+}
+
+// 資料夾路徑統一使用 '/'，去除空白與空的層級，不允許 '..'
+fn normalize_folder(folder: &str) -> Result<String, String> {
+    let mut parts = Vec::new();
+    for part in folder.split(['/', '\\']).map(str::trim).filter(|part| !part.is_empty()) {
+        if part == "." || part == ".." {
+            return Err(format!("無效的資料夾名稱: {folder}"));
+        }
+        parts.push(part);
+    }
+    Ok(parts.join("/"))
+}
+
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    tags.iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty() && seen.insert(tag.to_lowercase()))
+        .collect()
+}
+
+fn snippet_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Snippet, sqlx::Error> {
+    let tags: String = row.try_get("tags")?;
+    Ok(Snippet {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        folder: row.try_get("folder")?,
+        description: row.try_get("description")?,
+        sql: row.try_get("sql")?,
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        default_connection_id: row.try_get("default_connection_id")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+// 把使用者輸入轉成 FTS5 查詢：每個字詞都以前綴比對，全部符合才算符合
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+fn folder_prefix(folder: &str) -> String {
+    let escaped = folder.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("{escaped}/%")
+}
+
+#[tauri::command]
+pub async fn search_snippets(
+    app: AppHandle,
+    app_db: State<'_, AppDatabase>,
+    request: SearchSnippetsRequest,
+) -> Result<SearchSnippetsResult, String> {
+    let pool = app_db.pool(&app).await?;
+    let text = request.text.as_deref().and_then(fts_query);
+    let folder = match request.folder.as_deref() {
+        Some(folder) => Some(normalize_folder(folder)?).filter(|folder| !folder.is_empty()),
+        None => None,
+    };
+
+    let rows = sqlx::query(
+        "SELECT * FROM snippets
+         WHERE (?1 IS NULL OR id IN (SELECT rowid FROM snippets_fts WHERE snippets_fts MATCH ?1))
+           AND (?2 IS NULL OR folder = ?2 OR folder LIKE ?3 ESCAPE '\\')
+           AND (?4 IS NULL OR EXISTS (SELECT 1 FROM json_each(snippets.tags) WHERE value = ?4))
+           AND (?5 IS NULL OR default_connection_id = ?5)
+         ORDER BY folder, name
+         LIMIT ?6",
+    )
+    .bind(&text)
+    .bind(&folder)
+    .bind(folder.as_deref().map(folder_prefix))
+    .bind(&request.tag)
+    .bind(&request.connection_id)
+    .bind(request.limit as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("搜尋片段錯誤: {e}"))?;
+
+    let snippets = rows
+        .iter()
+        .map(snippet_from_row)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("讀取片段錯誤: {e}"))?;
+
+    let folders: Vec<String> = sqlx::query_scalar("SELECT DISTINCT folder FROM snippets WHERE folder <> '' ORDER BY folder")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("讀取資料夾錯誤: {e}"))?;
+    let tags: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT json_each.value FROM snippets, json_each(snippets.tags) ORDER BY json_each.value",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("讀取標籤錯誤: {e}"))?;
+
+    let count = snippets.len();
+    Ok(SearchSnippetsResult {
+        success: true,
+        snippets,
+        folders,
+        tags,
+        message: format!("找到 {count} 個片段"),
+    })
+}
+
+#[tauri::command]
+pub async fn save_snippet(
+    app: AppHandle,
+    app_db: State<'_, AppDatabase>,
+    request: SaveSnippetRequest,
+) -> Result<Snippet, String> {
+    let pool = app_db.pool(&app).await?;
+    let snippet = BundledSnippet {
+        name: request.name,
+        folder: request.folder,
+        description: request.description,
+        sql: request.sql,
+        tags: request.tags,
+        default_connection_id: request.default_connection_id,
+    };
+    let id = write_snippet(pool, request.id, snippet).await?;
+
+    let row = sqlx::query("SELECT * FROM snippets WHERE id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("讀取片段錯誤: {e}"))?;
+    snippet_from_row(&row).map_err(|e| format!("讀取片段錯誤: {e}"))
+}
+
+// 新增或更新片段，返回片段的 id
+async fn write_snippet(pool: &sqlx::SqlitePool, id: Option<i64>, snippet: BundledSnippet) -> Result<i64, String> {
+    let name = snippet.name.trim().to_string();
+    if name.is_empty() {
+        return Err("片段名稱不能為空".to_string());
+    }
+    let folder = normalize_folder(&snippet.folder)?;
+    let tags = serde_json::to_string(&normalize_tags(&snippet.tags)).map_err(|e| format!("序列化錯誤: {e}"))?;
+    let connection_id = snippet.default_connection_id.filter(|id| !id.is_empty());
+    let now = now_millis();
+
+    let result = match id {
+        Some(id) => sqlx::query(
+            "UPDATE snippets SET name = ?, folder = ?, description = ?, sql = ?, tags = ?, default_connection_id = ?, updated_at = ?
+             WHERE id = ?",
+        )
+        .bind(&name)
+        .bind(&folder)
+        .bind(&snippet.description)
+        .bind(&snippet.sql)
+        .bind(&tags)
+        .bind(&connection_id)
+        .bind(now)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|result| (result.rows_affected() > 0).then_some(id)),
+        None => sqlx::query(
+            "INSERT INTO snippets (name, folder, description, sql, tags, default_connection_id, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&name)
+        .bind(&folder)
+        .bind(&snippet.description)
+        .bind(&snippet.sql)
+        .bind(&tags)
+        .bind(&connection_id)
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map(|result| Some(result.last_insert_rowid())),
+    };
+
+    match result {
+        Ok(Some(id)) => Ok(id),
+        Ok(None) => Err(format!("找不到片段: {}", id.unwrap_or_default())),
+        Err(e) if e.as_database_error().is_some_and(|error| error.is_unique_violation()) => {
+            Err(format!("資料夾「{folder}」中已有名為「{name}」的片段"))
+        }
+        Err(e) => Err(format!("儲存片段錯誤: {e}")),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_snippets(app: AppHandle, app_db: State<'_, AppDatabase>, ids: Vec<i64>) -> Result<u64, String> {
+    let pool = app_db.pool(&app).await?;
+    let ids = serde_json::to_string(&ids).map_err(|e| format!("序列化錯誤: {e}"))?;
+    let result = sqlx::query("DELETE FROM snippets WHERE id IN (SELECT value FROM json_each(?))")
+        .bind(ids)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("刪除片段錯誤: {e}"))?;
+    Ok(result.rows_affected())
+}
+
+// 重新命名或移動資料夾，子資料夾一併移動，返回移動的片段數
+#[tauri::command]
+pub async fn rename_snippet_folder(
+    app: AppHandle,
+    app_db: State<'_, AppDatabase>,
+    from: String,
+    to: String,
+) -> Result<u64, String> {
+    let from = normalize_folder(&from)?;
+    let to = normalize_folder(&to)?;
+    if from.is_empty() {
+        return Err("不能重新命名根目錄".to_string());
+    }
+    if to == from || to.starts_with(&format!("{from}/")) {
+        return Err("不能把資料夾移動到自己之下".to_string());
+    }
+
+    let pool = app_db.pool(&app).await?;
+    let result = sqlx::query(
+        "UPDATE snippets SET folder = ?1 || substr(folder, length(?2) + 1), updated_at = ?4
+         WHERE folder = ?2 OR folder LIKE ?3 ESCAPE '\\'",
+    )
+    .bind(&to)
+    .bind(&from)
+    .bind(folder_prefix(&from))
+    .bind(now_millis())
+    .execute(pool)
+    .await;
+
+    match result {
+        Ok(result) => Ok(result.rows_affected()),
+        Err(e) if e.as_database_error().is_some_and(|error| error.is_unique_violation()) => {
+            Err(format!("資料夾「{to}」中已有同名的片段"))
+        }
+        Err(e) => Err(format!("移動資料夾錯誤: {e}")),
+    }
+}
+
+#[tauri::command]
+pub async fn export_snippets(
+    app: AppHandle,
+    app_db: State<'_, AppDatabase>,
+    request: ExportSnippetsRequest,
+) -> Result<SnippetTransferResult, String> {
+    let directory = match request.format.as_str() {
+        "json" => false,
+        "directory" => true,
+        other => return Err(format!("不支援的片段格式: {other}")),
+    };
+    let path = match &request.path {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => match choose_path(&app, directory, true).await? {
+            Some(path) => path,
+            None => return Ok(SnippetTransferResult::cancelled()),
+        },
+    };
+
+    let search = SearchSnippetsRequest {
+        folder: request.folder.clone(),
+        limit: u32::MAX,
+        ..SearchSnippetsRequest::default()
+    };
+    let snippets: Vec<Snippet> = search_snippets(app.clone(), app_db, search)
+        .await?
+        .snippets
+        .into_iter()
+        .filter(|snippet| request.ids.is_empty() || request.ids.contains(&snippet.id))
+        .collect();
+
+    let count = snippets.len() as u64;
+    let bundled = snippets.into_iter().map(|snippet| BundledSnippet {
+        name: snippet.name,
+        folder: snippet.folder,
+        description: snippet.description,
+        sql: snippet.sql,
+        tags: snippet.tags,
+        default_connection_id: snippet.default_connection_id,
+    });
+    if directory {
+        write_snippet_directory(&path, bundled)?;
+    } else {
+        let bundle = SnippetBundle {
+            version: BUNDLE_VERSION,
+            snippets: bundled.collect(),
+        };
+        let file = File::create(&path).map_err(|e| format!("無法建立檔案: {e}"))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), &bundle).map_err(|e| format!("寫入檔案錯誤: {e}"))?;
+    }
+
+    Ok(SnippetTransferResult {
+        success: true,
+        path: Some(path.to_string_lossy().into_owned()),
+        created: count,
+        updated: 0,
+        skipped: 0,
+        message: format!("已匯出 {count} 個片段"),
+    })
+}
+
+#[tauri::command]
+pub async fn import_snippets(
+    app: AppHandle,
+    app_db: State<'_, AppDatabase>,
+    request: ImportSnippetsRequest,
+) -> Result<SnippetTransferResult, String> {
+    let directory = match request.format.as_str() {
+        "json" => false,
+        "directory" => true,
+        other => return Err(format!("不支援的片段格式: {other}")),
+    };
+    let path = match &request.path {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => match choose_path(&app, directory, false).await? {
+            Some(path) => path,
+            None => return Ok(SnippetTransferResult::cancelled()),
+        },
+    };
+
+    let snippets = if directory {
+        read_snippet_directory(&path)?
+    } else {
+        let file = File::open(&path).map_err(|e| format!("無法開啟檔案: {e}"))?;
+        let bundle: SnippetBundle = serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|e| format!("無效的片段檔案: {e}"))?;
+        if bundle.version > BUNDLE_VERSION {
+            let version = bundle.version;
+            return Err(format!("不支援的片段檔案版本: {version}"));
+        }
+        bundle.snippets
+    };
+    let base_folder = normalize_folder(request.folder.as_deref().unwrap_or_default())?;
+
+    let pool = app_db.pool(&app).await?;
+    let (mut created, mut updated, mut skipped) = (0, 0, 0);
+    for mut snippet in snippets {
+        let folder = normalize_folder(&snippet.folder)?;
+        snippet.folder = match (base_folder.is_empty(), folder.is_empty()) {
+            (true, _) => folder,
+            (false, true) => base_folder.clone(),
+            (false, false) => format!("{base_folder}/{folder}"),
+        };
+
+        let existing: Option<i64> = sqlx::query_scalar("SELECT id FROM snippets WHERE folder = ? AND name = ?")
+            .bind(&snippet.folder)
+            .bind(snippet.name.trim())
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("讀取片段錯誤: {e}"))?;
+        match existing {
+            Some(_) if !request.overwrite => skipped += 1,
+            Some(id) => {
+                write_snippet(pool, Some(id), snippet).await?;
+                updated += 1;
+            }
+            None => {
+                write_snippet(pool, None, snippet).await?;
+                created += 1;
+            }
+        }
+    }
+
+    Ok(SnippetTransferResult {
+        success: true,
+        path: Some(path.to_string_lossy().into_owned()),
+        created,
+        updated,
+        skipped,
+        message: format!("新增 {created} 個片段，更新 {updated} 個，略過 {skipped} 個"),
+    })
+}
+
+async fn choose_path(app: &AppHandle, directory: bool, save: bool) -> Result<Option<PathBuf>, String> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    let dialog = app.dialog().file();
+
+    if directory {
+        dialog.pick_folder(move |path| {
+            let _ = sender.send(path);
+        });
+    } else if save {
+        dialog
+            .add_filter("JSON", &["json"])
+            .set_file_name("snippets.json")
+            .save_file(move |path| {
+                let _ = sender.send(path);
+            });
+    } else {
+        dialog.add_filter("JSON", &["json"]).pick_file(move |path| {
+            let _ = sender.send(path);
+        });
+    }
+
+    match receiver.await.map_err(|e| format!("選擇檔案對話框錯誤: {e}"))? {
+        Some(path) => path.into_path().map(Some).map_err(|e| format!("無效的檔案路徑: {e}")),
+        None => Ok(None),
+    }
+}
+
+// 每個片段一個 .sql 檔案，資料夾對應目錄，其他資訊寫在開頭的註解中
+fn write_snippet_directory(root: &Path, snippets: impl Iterator<Item = BundledSnippet>) -> Result<(), String> {
+    let mut used = HashSet::new();
+    for snippet in snippets {
+        let directory = snippet
+            .folder
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .fold(root.to_path_buf(), |path, part| path.join(file_name(part)));
+        std::fs::create_dir_all(&directory).map_err(|e| format!("建立目錄錯誤: {e}"))?;
+
+        let stem = file_name(&snippet.name);
+        let mut path = directory.join(format!("{stem}.sql"));
+        let mut suffix = 2;
+        while !used.insert(path.clone()) {
+            path = directory.join(format!("{stem}-{suffix}.sql"));
+            suffix += 1;
+        }
+
+        let mut content = format!("-- name: {}\n", snippet.name);
+        for line in snippet.description.lines() {
+            content.push_str(&format!("-- description: {line}\n"));
+        }
+        if !snippet.tags.is_empty() {
+            content.push_str(&format!("-- tags: {}\n", snippet.tags.join(", ")));
+        }
+        if let Some(connection_id) = &snippet.default_connection_id {
+            content.push_str(&format!("-- connection: {connection_id}\n"));
+        }
+        content.push('\n');
+        content.push_str(&snippet.sql);
+        if !snippet.sql.ends_with('\n') {
+            content.push('\n');
+        }
+        std::fs::write(&path, content).map_err(|e| format!("寫入檔案錯誤: {e}"))?;
+    }
+    Ok(())
+}
+
+// 檔名中不能使用的字元改為底線
+fn file_name(name: &str) -> String {
+    let cleaned: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim_matches('.').to_string();
+    if cleaned.is_empty() {
+        "snippet".to_string()
+    } else {
+        cleaned
+    }
+}
+
+fn read_snippet_directory(root: &Path) -> Result<Vec<BundledSnippet>, String> {
+    let mut snippets = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(directory) = pending.pop() {
+        let entries = std::fs::read_dir(&directory).map_err(|e| format!("讀取目錄錯誤: {e}"))?;
+        for entry in entries {
+            let path = entry.map_err(|e| format!("讀取目錄錯誤: {e}"))?.path();
+            let hidden = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            if hidden {
+                continue;
+            }
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("sql")) {
+                snippets.push(read_snippet_file(root, &path)?);
+            }
+        }
+    }
+    Ok(snippets)
+}
+
+fn read_snippet_file(root: &Path, path: &Path) -> Result<BundledSnippet, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("讀取檔案錯誤: {e}"))?;
+    let folder = path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(root).ok())
+        .map(|relative| {
+            let parts: Vec<_> = relative.components().map(|part| part.as_os_str().to_string_lossy()).collect();
+            parts.join("/")
+        })
+        .unwrap_or_default();
+    let mut snippet = BundledSnippet {
+        name: path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default(),
+        folder,
+        description: String::new(),
+        sql: String::new(),
+        tags: vec![],
+        default_connection_id: None,
+    };
+
+    // 開頭連續的 "-- key: value" 註解是片段資訊，之後的空行與內容是 SQL
+    let mut descriptions = Vec::new();
+    let mut body_start = 0;
+    for line in content.split_inclusive('\n') {
+        let header = line.trim_end().strip_prefix("-- ");
+        match header.and_then(|header| header.split_once(": ").or_else(|| header.strip_suffix(':').map(|key| (key, "")))) {
+            Some(("name", value)) => snippet.name = value.to_string(),
+            Some(("description", value)) => descriptions.push(value.to_string()),
+            Some(("tags", value)) => snippet.tags = value.split(',').map(|tag| tag.trim().to_string()).collect(),
+            Some(("connection", value)) => snippet.default_connection_id = Some(value.to_string()),
+            _ => break,
+        }
+        body_start += line.len();
+    }
+    snippet.description = descriptions.join("\n");
+    let body = &content[body_start..];
+    snippet.sql = body.strip_prefix("\r\n").or_else(|| body.strip_prefix('\n')).unwrap_or(body).to_string();
+    Ok(snippet)
+}
@@ -0,0 +1,237 @@
+use serde_json::{json, Map, Value};
+
+use crate::binary::to_hex;
+
+// EWKB 型別欄位中的旗標
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+// SpatiaLite 二進位幾何格式的固定標記
+const SPATIALITE_START: u8 = 0x00;
+const SPATIALITE_MBR_END: u8 = 0x7C;
+const SPATIALITE_ENTITY: u8 = 0x69;
+const SPATIALITE_END: u8 = 0xFE;
+const SPATIALITE_HEADER_LEN: usize = 43;
+
+// 集合中子幾何的標頭：WKB 每個子幾何都有位元組順序，SpatiaLite 以 0x69 標記開頭
+#[derive(Clone, Copy)]
+enum Format {
+    Wkb,
+    SpatiaLite,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    little_endian: bool,
+}
+
+impl Reader<'_> {
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        if self.bytes.len() < N {
+            return None;
+        }
+        let (head, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        head.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.array::<1>().map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.array()?;
+        Some(if self.little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    }
+
+    fn f64(&mut self) -> Option<f64> {
+        let bytes = self.array()?;
+        Some(if self.little_endian { f64::from_le_bytes(bytes) } else { f64::from_be_bytes(bytes) })
+    }
+}
+
+// 幾何型別與座標維度，同時支援 EWKB 旗標與 ISO WKB 的 1000/2000/3000 型別代碼
+struct GeometryType {
+    kind: u32,
+    has_z: bool,
+    has_m: bool,
+}
+
+impl GeometryType {
+    fn new(code: u32) -> Self {
+        let base = code & 0x0FFF_FFFF;
+        let dimensions = base / 1000;
+        Self {
+            // SpatiaLite 的壓縮幾何代碼大於 1000000，不支援
+            kind: if dimensions <= 3 { base % 1000 } else { 0 },
+            has_z: code & EWKB_Z != 0 || dimensions == 1 || dimensions == 3,
+            has_m: code & EWKB_M != 0 || dimensions == 2 || dimensions == 3,
+        }
+    }
+}
+
+// 查詢結果以 GeoJSON 顯示幾何；匯出與傾印需要能還原，保留資料庫的二進位格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GeometryFormat {
+    GeoJson,
+    Native,
+}
+
+// 匯出與傾印中的幾何：$geometry 為十六進位的 WKB，srid 為 None 時是內含 SRID 的 EWKB
+pub(crate) fn geometry_value(wkb: &[u8], srid: Option<u32>) -> Value {
+    json!({ "$geometry": to_hex(wkb), "srid": srid })
+}
+
+// MySQL 內部格式拆成 SRID 與 WKB
+pub(crate) fn mysql_geometry_value(bytes: &[u8]) -> Option<Value> {
+    let (srid, wkb) = bytes.split_at_checked(4)?;
+    Some(geometry_value(wkb, Some(u32::from_le_bytes(srid.try_into().ok()?))))
+}
+
+pub(crate) fn geometry_hex(value: &Value) -> Option<(&str, Option<u32>)> {
+    let object = value.as_object()?;
+    let hex = object.get("$geometry")?.as_str()?;
+    let srid = object.get("srid").and_then(Value::as_u64).and_then(|srid| u32::try_from(srid).ok());
+    Some((hex, srid))
+}
+
+// PostGIS geometry/geography 的 EWKB，或一般的 WKB
+pub(crate) fn wkb_geojson(bytes: &[u8]) -> Option<Value> {
+    let mut reader = Reader { bytes, little_endian: true };
+    let (geometry, srid) = wkb_geometry(&mut reader)?;
+    finish(geometry, srid, &reader)
+}
+
+// MySQL 內部的幾何格式：4 位元組 little-endian 的 SRID 加上 WKB
+pub(crate) fn mysql_geojson(bytes: &[u8]) -> Option<Value> {
+    let (srid, wkb) = bytes.split_at_checked(4)?;
+    let srid = u32::from_le_bytes(srid.try_into().ok()?);
+    let mut reader = Reader { bytes: wkb, little_endian: true };
+    let (geometry, _) = wkb_geometry(&mut reader)?;
+    finish(geometry, Some(srid), &reader)
+}
+
+// SpatiaLite 的幾何 BLOB，不符合格式（包括壓縮的幾何）時返回 None
+pub(crate) fn spatialite_geojson(bytes: &[u8]) -> Option<Value> {
+    if bytes.len() <= SPATIALITE_HEADER_LEN
+        || bytes[0] != SPATIALITE_START
+        || bytes[1] > 1
+        || bytes[38] != SPATIALITE_MBR_END
+        || bytes[bytes.len() - 1] != SPATIALITE_END
+    {
+        return None;
+    }
+
+    let mut header = Reader { bytes: &bytes[2..6], little_endian: bytes[1] == 1 };
+    let srid = header.u32()?;
+    let mut reader = Reader {
+        bytes: &bytes[39..bytes.len() - 1],
+        little_endian: bytes[1] == 1,
+    };
+    let geometry_type = GeometryType::new(reader.u32()?);
+    let geometry = geometry_body(&mut reader, &geometry_type, Format::SpatiaLite)?;
+    finish(geometry, Some(srid), &reader)
+}
+
+// 資料必須剛好讀完，SRID 以 crs 成員保留
+fn finish(mut geometry: Value, srid: Option<u32>, reader: &Reader) -> Option<Value> {
+    if !reader.bytes.is_empty() {
+        return None;
+    }
+    if let (Some(srid), Some(object)) = (srid.filter(|&srid| srid > 0), geometry.as_object_mut()) {
+        object.insert(
+            "crs".to_string(),
+            json!({ "type": "name", "properties": { "name": format!("EPSG:{srid}") } }),
+        );
+    }
+    Some(geometry)
+}
+
+fn wkb_geometry(reader: &mut Reader) -> Option<(Value, Option<u32>)> {
+    reader.little_endian = match reader.u8()? {
+        0 => false,
+        1 => true,
+        _ => return None,
+    };
+    let code = reader.u32()?;
+    let srid = if code & EWKB_SRID != 0 { Some(reader.u32()?) } else { None };
+    let geometry = geometry_body(reader, &GeometryType::new(code), Format::Wkb)?;
+    Some((geometry, srid))
+}
+
+fn child_geometry(reader: &mut Reader, format: Format) -> Option<Value> {
+    match format {
+        Format::Wkb => wkb_geometry(reader).map(|(geometry, _)| geometry),
+        Format::SpatiaLite => {
+            if reader.u8()? != SPATIALITE_ENTITY {
+                return None;
+            }
+            let geometry_type = GeometryType::new(reader.u32()?);
+            geometry_body(reader, &geometry_type, format)
+        }
+    }
+}
+
+fn geometry_body(reader: &mut Reader, geometry_type: &GeometryType, format: Format) -> Option<Value> {
+    let (name, coordinates) = match geometry_type.kind {
+        1 => ("Point", point(reader, geometry_type)?),
+        2 => ("LineString", points(reader, geometry_type)?),
+        3 => ("Polygon", rings(reader, geometry_type)?),
+        4..=6 => {
+            let count = reader.u32()?;
+            let mut children = Vec::new();
+            for _ in 0..count {
+                let mut child = child_geometry(reader, format)?;
+                children.push(child.get_mut("coordinates")?.take());
+            }
+            let name = match geometry_type.kind {
+                4 => "MultiPoint",
+                5 => "MultiLineString",
+                _ => "MultiPolygon",
+            };
+            (name, Value::Array(children))
+        }
+        7 => {
+            let count = reader.u32()?;
+            let geometries = (0..count).map(|_| child_geometry(reader, format)).collect::<Option<Vec<_>>>()?;
+            return Some(json!({ "type": "GeometryCollection", "geometries": geometries }));
+        }
+        // 曲線等 GeoJSON 無法表示的型別
+        _ => return None,
+    };
+
+    let mut object = Map::new();
+    object.insert("type".to_string(), Value::from(name));
+    object.insert("coordinates".to_string(), coordinates);
+    Some(Value::Object(object))
+}
+
+// GeoJSON 只保留 X、Y 與 Z，M 值會被略過；空的 POINT 以 NaN 表示
+fn point(reader: &mut Reader, geometry_type: &GeometryType) -> Option<Value> {
+    let x = reader.f64()?;
+    let y = reader.f64()?;
+    let z = if geometry_type.has_z { Some(reader.f64()?) } else { None };
+    if geometry_type.has_m {
+        reader.f64()?;
+    }
+    if x.is_nan() && y.is_nan() {
+        return Some(Value::Array(vec![]));
+    }
+
+    let mut position = vec![json!(x), json!(y)];
+    if let Some(z) = z {
+        position.push(json!(z));
+    }
+    Some(Value::Array(position))
+}
+
+fn points(reader: &mut Reader, geometry_type: &GeometryType) -> Option<Value> {
+    let count = reader.u32()?;
+    (0..count).map(|_| point(reader, geometry_type)).collect::<Option<Vec<_>>>().map(Value::Array)
+}
+
+fn rings(reader: &mut Reader, geometry_type: &GeometryType) -> Option<Value> {
+    let count = reader.u32()?;
+    (0..count).map(|_| points(reader, geometry_type)).collect::<Option<Vec<_>>>().map(Value::Array)
+}
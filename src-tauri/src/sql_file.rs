@@ -0,0 +1,419 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sqlx::Executor;
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::connection::{connect_mysql, connect_postgres, connect_sqlite};
+use crate::sql_guard::ensure_writable;
+use crate::sql_splitter::{statement_keyword, SplitStatement, StatementSplitter};
+use crate::DatabaseConnection;
+
+// 進度事件的最短間隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+// 錯誤報告中保留的語句長度
+const MAX_ERROR_STATEMENT_CHARS: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunSqlFileRequest {
+    pub connection: DatabaseConnection,
+    pub file_path: Option<String>, // 未指定時開啟選擇檔案對話框
+    pub run_id: Option<String>, // 用於停止執行，未指定時自動產生
+    #[serde(default)]
+    pub resume_from: usize, // 從第幾條語句（從 0 開始）繼續執行
+    #[serde(default)]
+    pub options: RunSqlFileOptions,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunSqlFileOptions {
+    pub continue_on_error: bool,
+    pub max_reported_errors: usize,
+}
+
+impl Default for RunSqlFileOptions {
+    fn default() -> Self {
+        Self {
+            continue_on_error: false,
+            max_reported_errors: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SqlFileError {
+    pub statement_index: usize,
+    pub line: usize,
+    pub statement: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunSqlFileResult {
+    pub success: bool,
+    pub run_id: String,
+    pub file_path: Option<String>,
+    pub statements_executed: u64,
+    pub statements_failed: u64,
+    pub next_statement: usize, // 停止或中止時，續傳應使用的 resume_from
+    pub stopped: bool,
+    pub errors: Vec<SqlFileError>,
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+    pub execution_time: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SqlFileProgress {
+    pub run_id: String,
+    pub file_path: String,
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+    pub statements_executed: u64,
+    pub statements_failed: u64,
+    pub current_line: usize,
+}
+
+// 執行中的 SQL 檔案，供 stop_sql_file 設定停止旗標
+#[derive(Default)]
+pub(crate) struct SqlFileRuns(Mutex<HashMap<String, Arc<AtomicBool>>>);
+
+impl SqlFileRuns {
+    fn register(&self, run_id: &str) -> Arc<AtomicBool> {
+        let stop = Arc::new(AtomicBool::new(false));
+        self.0.lock().unwrap().insert(run_id.to_string(), stop.clone());
+        stop
+    }
+
+    fn unregister(&self, run_id: &str) {
+        self.0.lock().unwrap().remove(run_id);
+    }
+}
+
+#[tauri::command]
+pub async fn run_sql_file(
+    app: AppHandle,
+    runs: State<'_, SqlFileRuns>,
+    request: RunSqlFileRequest,
+) -> Result<RunSqlFileResult, String> {
+    let start_time = std::time::Instant::now();
+    ensure_writable(&request.connection, "執行 SQL 檔案")?;
+    let run_id = request.run_id.clone()
+        .filter(|run_id| !run_id.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let path = match &request.file_path {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => match choose_sql_file(&app).await? {
+            Some(path) => path,
+            None => return Ok(RunSqlFileResult::cancelled(run_id, request.resume_from)),
+        },
+    };
+
+    let stop = runs.register(&run_id);
+    let result = run_file(&app, &request, &path, &run_id, stop).await;
+    runs.unregister(&run_id);
+    let execution_time = start_time.elapsed().as_millis() as u64;
+
+    let run = match result {
+        Ok(run) => run,
+        Err(error) => {
+            return Ok(RunSqlFileResult {
+                success: false,
+                run_id,
+                file_path: Some(path.to_string_lossy().into_owned()),
+                statements_executed: 0,
+                statements_failed: 0,
+                next_statement: request.resume_from,
+                stopped: false,
+                errors: vec![],
+                bytes_read: 0,
+                total_bytes: 0,
+                execution_time,
+                message: format!("執行失敗: {error}"),
+            })
+        }
+    };
+
+    let (executed, failed, next) = (run.statements_executed, run.statements_failed, run.next_statement);
+    let message = if run.stopped {
+        format!("已停止，執行 {executed} 條語句，可從第 {next} 條繼續")
+    } else if let (true, Some(error)) = (run.aborted, run.errors.last()) {
+        let (line, reason) = (error.line, &error.error);
+        format!("第 {line} 行的語句執行失敗: {reason}（已執行 {executed} 條語句）")
+    } else {
+        format!("執行完成，成功 {executed} 條語句，失敗 {failed} 條")
+    };
+
+    Ok(RunSqlFileResult {
+        success: !run.stopped && !run.aborted && failed == 0,
+        run_id,
+        file_path: Some(path.to_string_lossy().into_owned()),
+        statements_executed: executed,
+        statements_failed: failed,
+        next_statement: next,
+        stopped: run.stopped,
+        errors: run.errors,
+        bytes_read: run.reader.bytes_read,
+        total_bytes: run.total_bytes,
+        execution_time,
+        message,
+    })
+}
+
+// 在下一條語句開始前停止，已執行的語句不會回滾
+#[tauri::command]
+pub async fn stop_sql_file(runs: State<'_, SqlFileRuns>, run_id: String) -> Result<bool, String> {
+    match runs.0.lock().unwrap().get(&run_id) {
+        Some(stop) => {
+            stop.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+impl RunSqlFileResult {
+    fn cancelled(run_id: String, resume_from: usize) -> Self {
+        Self {
+            success: false,
+            run_id,
+            file_path: None,
+            statements_executed: 0,
+            statements_failed: 0,
+            next_statement: resume_from,
+            stopped: false,
+            errors: vec![],
+            bytes_read: 0,
+            total_bytes: 0,
+            execution_time: 0,
+            message: "已取消執行".to_string(),
+        }
+    }
+}
+
+async fn choose_sql_file(app: &AppHandle) -> Result<Option<PathBuf>, String> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+
+    app.dialog()
+        .file()
+        .add_filter("SQL 檔案", &["sql", "txt"])
+        .pick_file(move |path| {
+            let _ = sender.send(path);
+        });
+
+    match receiver.await.map_err(|e| format!("選擇檔案對話框錯誤: {e}"))? {
+        Some(path) => path.into_path().map(Some).map_err(|e| format!("無效的檔案路徑: {e}")),
+        None => Ok(None),
+    }
+}
+
+// 逐行讀取檔案並切分語句，整個檔案不會同時留在記憶體中
+struct SqlFileReader {
+    reader: BufReader<File>,
+    splitter: StatementSplitter,
+    pending: VecDeque<SplitStatement>,
+    line: Vec<u8>,
+    bytes_read: u64,
+    finished: bool,
+}
+
+impl SqlFileReader {
+    fn next_statement(&mut self) -> Result<Option<SplitStatement>, String> {
+        loop {
+            if let Some(statement) = self.pending.pop_front() {
+                return Ok(Some(statement));
+            }
+            if self.finished {
+                return Ok(None);
+            }
+
+            self.line.clear();
+            let read = self.reader.read_until(b'\n', &mut self.line).map_err(|e| format!("讀取檔案錯誤: {e}"))?;
+            if read == 0 {
+                self.finished = true;
+                self.pending.extend(self.splitter.finish());
+                continue;
+            }
+
+            let text = String::from_utf8_lossy(&self.line);
+            // 略過 UTF-8 BOM
+            let text = if self.bytes_read == 0 { text.trim_start_matches('\u{feff}') } else { &text };
+            self.bytes_read += read as u64;
+            self.pending.extend(self.splitter.push(text));
+        }
+    }
+}
+
+struct SqlFileRun {
+    app: AppHandle,
+    run_id: String,
+    file_path: String,
+    db_type: String,
+    reader: SqlFileReader,
+    stop: Arc<AtomicBool>,
+    resume_from: usize,
+    continue_on_error: bool,
+    max_reported_errors: usize,
+    total_bytes: u64,
+    next_statement: usize,
+    statements_executed: u64,
+    statements_failed: u64,
+    errors: Vec<SqlFileError>,
+    stopped: bool,
+    aborted: bool,
+    last_progress: Instant,
+}
+
+impl SqlFileRun {
+    fn emit_progress(&mut self, current_line: usize, force: bool) {
+        if !force && self.last_progress.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        self.last_progress = Instant::now();
+        let _ = self.app.emit("sql-file-progress", SqlFileProgress {
+            run_id: self.run_id.clone(),
+            file_path: self.file_path.clone(),
+            bytes_read: self.reader.bytes_read,
+            total_bytes: self.total_bytes,
+            statements_executed: self.statements_executed,
+            statements_failed: self.statements_failed,
+            current_line,
+        });
+    }
+
+    fn record_error(&mut self, statement: &SplitStatement, error: String) {
+        self.statements_failed += 1;
+        if self.errors.len() < self.max_reported_errors {
+            self.errors.push(SqlFileError {
+                statement_index: self.next_statement,
+                line: statement.line,
+                statement: statement.sql.chars().take(MAX_ERROR_STATEMENT_CHARS).collect(),
+                error,
+            });
+        }
+    }
+}
+
+// 續傳時略過已執行的語句，但連接層級的設定（SET NAMES、search_path 等）仍需重新執行
+fn is_session_statement(sql: &str, db_type: &str) -> bool {
+    match statement_keyword(sql, db_type).as_str() {
+        "SET" | "USE" => true,
+        "SELECT" => sql.contains("set_config("),
+        _ => false,
+    }
+}
+
+async fn run_file(
+    app: &AppHandle,
+    request: &RunSqlFileRequest,
+    path: &PathBuf,
+    run_id: &str,
+    stop: Arc<AtomicBool>,
+) -> Result<SqlFileRun, String> {
+    let connection = &request.connection;
+    let file = File::open(path).map_err(|e| format!("無法開啟檔案: {e}"))?;
+    let total_bytes = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+
+    let mut run = SqlFileRun {
+        app: app.clone(),
+        run_id: run_id.to_string(),
+        file_path: path.to_string_lossy().into_owned(),
+        db_type: connection.db_type.clone(),
+        reader: SqlFileReader {
+            reader: BufReader::new(file),
+            splitter: StatementSplitter::new(&connection.db_type),
+            pending: VecDeque::new(),
+            line: Vec::new(),
+            bytes_read: 0,
+            finished: false,
+        },
+        stop,
+        resume_from: request.resume_from,
+        continue_on_error: request.options.continue_on_error,
+        max_reported_errors: request.options.max_reported_errors,
+        total_bytes,
+        next_statement: 0,
+        statements_executed: 0,
+        statements_failed: 0,
+        errors: vec![],
+        stopped: false,
+        aborted: false,
+        last_progress: Instant::now(),
+    };
+
+    match connection.db_type.as_str() {
+        "sqlite" => {
+            let pool = connect_sqlite(connection).await?;
+            let result = run_statements(&pool, &mut run).await;
+            pool.close().await;
+            result?
+        }
+        "mysql" => {
+            let pool = connect_mysql(connection).await?;
+            let result = run_statements(&pool, &mut run).await;
+            pool.close().await;
+            result?
+        }
+        "postgresql" => {
+            let pool = connect_postgres(connection).await?;
+            let result = run_statements(&pool, &mut run).await;
+            pool.close().await;
+            result?
+        }
+        _ => return Err("不支援的資料庫類型".to_string()),
+    }
+
+    Ok(run)
+}
+
+// 所有語句都在同一個連接上執行，讓 SET、USE 等設定對後續語句生效
+async fn run_statements<DB>(pool: &sqlx::Pool<DB>, run: &mut SqlFileRun) -> Result<(), String>
+where
+    DB: sqlx::Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    let mut conn = pool.acquire().await.map_err(|e| format!("取得連接錯誤: {e}"))?;
+    let mut current_line = 1;
+
+    while let Some(statement) = run.reader.next_statement()? {
+        current_line = statement.line;
+        let skipped = run.next_statement < run.resume_from;
+        if skipped && !is_session_statement(&statement.sql, &run.db_type) {
+            run.next_statement += 1;
+            continue;
+        }
+        if run.stop.load(Ordering::Relaxed) {
+            run.stopped = true;
+            break;
+        }
+
+        match (&mut *conn).execute(sqlx::raw_sql(&statement.sql)).await {
+            Ok(_) if !skipped => run.statements_executed += 1,
+            Ok(_) => {}
+            Err(e) => {
+                run.record_error(&statement, e.to_string());
+                if !run.continue_on_error {
+                    // 中止時保留失敗語句的序號，修正後可從該語句重新執行
+                    run.aborted = true;
+                    break;
+                }
+            }
+        }
+
+        run.next_statement += 1;
+        run.emit_progress(current_line, false);
+    }
+
+    run.emit_progress(current_line, true);
+    Ok(())
+}
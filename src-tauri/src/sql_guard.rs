@@ -0,0 +1,613 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::connection::DbPool;
+use crate::sql_splitter::{dashes_start_comment, split_statements, statement_start};
+use crate::DatabaseConnection;
+
+// 確認 token 的有效時間
+const CONFIRMATION_TTL: Duration = Duration::from_secs(600);
+
+// 可以作為語句主體的關鍵字，用於找出 WITH 與 EXPLAIN 之後真正執行的語句
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "SELECT", "VALUES", "TABLE", "INSERT", "UPDATE", "DELETE", "REPLACE", "MERGE",
+];
+
+// 會修改資料的語句，在 WITH 與 EXPLAIN ANALYZE 中可能出現在任何括號深度
+const DATA_MODIFYING_KEYWORDS: &[&str] = &["INSERT", "UPDATE", "DELETE", "MERGE"];
+
+// 能把唯讀的工作階段或交易改回可寫入的設定名稱與函式
+const READ_ONLY_SETTINGS: &[&str] = &[
+    "DEFAULT_TRANSACTION_READ_ONLY", "TRANSACTION_READ_ONLY", "TX_READ_ONLY", "SET_CONFIG",
+];
+
+// 帶參數時只讀取資訊的 PRAGMA，其他 PRAGMA 帶參數時會修改設定
+const READ_PRAGMAS: &[&str] = &[
+    "TABLE_INFO", "TABLE_XINFO", "TABLE_LIST", "INDEX_LIST", "INDEX_INFO", "INDEX_XINFO",
+    "FOREIGN_KEY_LIST", "FOREIGN_KEY_CHECK", "INTEGRITY_CHECK", "QUICK_CHECK",
+];
+
+// 語句中的字詞與所在的括號深度，略過字串、引號識別字與註解
+fn statement_words(sql: &str, db_type: &str) -> Vec<(usize, String)> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut words = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        match c {
+            '\'' | '"' | '`' => {
+                i += 1;
+                while i < chars.len() {
+                    if c == '\'' && chars[i] == '\\' && db_type == "mysql" {
+                        i += 2;
+                        continue;
+                    }
+                    if chars[i] == c {
+                        // 連續兩個引號是跳脫
+                        if chars.get(i + 1) == Some(&c) {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+            }
+            '-' if next == Some('-') && dashes_start_comment(chars.get(i + 2).copied(), db_type) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '#' if db_type == "mysql" => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            // MySQL 的 /*! ... */ 條件註解會被執行，只略過開頭的版本號
+            '/' if next == Some('*') && db_type == "mysql" && chars.get(i + 2) == Some(&'!') => {
+                i += 3;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                    i += 1;
+                }
+                words.push((depth, chars[start..i].iter().collect::<String>().to_uppercase()));
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    words
+}
+
+// 語句的主要關鍵字，WITH 開頭時返回 CTE 之後的主語句關鍵字
+pub(crate) fn main_keyword(sql: &str, db_type: &str) -> String {
+    let words = statement_words(sql, db_type);
+    match words.first() {
+        Some((_, first)) if first == "WITH" => words
+            .iter()
+            .skip(1)
+            .find(|(depth, word)| *depth == 0 && STATEMENT_KEYWORDS.contains(&word.as_str()))
+            .map(|(_, word)| word.clone())
+            .unwrap_or_else(|| first.clone()),
+        Some((_, first)) => first.clone(),
+        None => String::new(),
+    }
+}
+
+// 任何括號深度中的 INSERT、UPDATE、DELETE 或 MERGE，略過 FOR UPDATE 等鎖定子句
+fn modifies_data(words: &[(usize, String)]) -> bool {
+    words.iter().enumerate().any(|(i, (_, word))| {
+        DATA_MODIFYING_KEYWORDS.contains(&word.as_str())
+            && !(word == "UPDATE" && i > 0 && matches!(words[i - 1].1.as_str(), "FOR" | "KEY"))
+    })
+}
+
+// 只讀取資料、不會修改資料庫的語句
+pub(crate) fn is_read_statement(sql: &str, db_type: &str) -> bool {
+    let words = statement_words(sql, db_type);
+    let top_level = |word: &str| words.iter().any(|(depth, w)| *depth == 0 && w == word);
+    let keyword = main_keyword(sql, db_type);
+
+    // 不能把工作階段或交易改回可寫入，包括 BEGIN READ WRITE 與 set_config('default_transaction_read_only', ...)
+    // SET 的設定名稱可能加上引號，statement_words 不會返回引號中的文字，因此另外比對原文
+    let read_write = words.windows(2).any(|pair| pair[0].1 == "READ" && pair[1].1 == "WRITE");
+    let read_only_setting = words.iter().any(|(_, word)| READ_ONLY_SETTINGS.contains(&word.as_str()))
+        || (matches!(keyword.as_str(), "SET" | "RESET") && sql.to_uppercase().contains("READ_ONLY"));
+    if keyword != "SHOW" && (read_write || read_only_setting) {
+        return false;
+    }
+    // PostgreSQL 的 WITH 可以包含修改資料的子句，主語句仍是 SELECT
+    if words.first().is_some_and(|(_, first)| first == "WITH") && modifies_data(&words) {
+        return false;
+    }
+
+    match keyword.as_str() {
+        // SELECT ... INTO 會建立表格或寫出檔案
+        "SELECT" => !top_level("INTO"),
+        "VALUES" | "TABLE" | "SHOW" | "DESCRIBE" | "DESC" | "USE" => true,
+        "BEGIN" | "START" | "COMMIT" | "ROLLBACK" | "END" => true,
+        // 全域設定與密碼會寫入伺服器，只允許工作階段層級的 SET
+        "SET" => !["GLOBAL", "PERSIST", "PERSIST_ONLY", "PASSWORD"].iter().any(|word| top_level(word)),
+        // RESET ALL 與 RESET SESSION AUTHORIZATION 會還原連接時設定的唯讀模式；MySQL 的 RESET 都是管理指令
+        "RESET" => db_type != "mysql" && !matches!(words.get(1).map(|(_, word)| word.as_str()), Some("ALL" | "SESSION")),
+        "PRAGMA" => {
+            let name = words.get(1).map(|(_, word)| word.as_str()).unwrap_or("");
+            !sql.contains('=') && (!sql.contains('(') || READ_PRAGMAS.contains(&name))
+        }
+        // EXPLAIN ANALYZE 會實際執行語句，只有被解釋的語句也是唯讀時才允許
+        "EXPLAIN" => {
+            let inner = words.iter().skip(1).position(|(depth, word)| *depth == 0 && STATEMENT_KEYWORDS.contains(&word.as_str()));
+            let options = &words[1..inner.map_or(words.len(), |index| index + 1)];
+            let analyze = options.iter().any(|(_, word)| word == "ANALYZE");
+            match inner {
+                Some(index) if analyze => {
+                    let keyword = &words[index + 1].1;
+                    (keyword == "SELECT" || keyword == "VALUES" || keyword == "TABLE") && !modifies_data(&words)
+                }
+                _ => true,
+            }
+        }
+        _ => false,
+    }
+}
+
+// 會返回資料列的語句，以串流讀取並套用行數上限；其他語句只返回影響的行數
+pub(crate) fn returns_rows(sql: &str, db_type: &str) -> bool {
+    let words = statement_words(sql, db_type);
+    let top_level = |word: &str| words.iter().any(|(depth, w)| *depth == 0 && w == word);
+
+    match main_keyword(sql, db_type).as_str() {
+        // SELECT ... INTO 建立表格或寫入變數，不返回資料列
+        "SELECT" => !top_level("INTO"),
+        "VALUES" | "TABLE" | "SHOW" | "DESCRIBE" | "DESC" | "EXPLAIN" => true,
+        // 設定值的 PRAGMA 不返回資料列
+        "PRAGMA" => !sql.contains('='),
+        "INSERT" | "UPDATE" | "DELETE" | "REPLACE" | "MERGE" => top_level("RETURNING"),
+        _ => false,
+    }
+}
+
+// 唯讀連接只允許讀取語句，在送出前逐條檢查
+pub(crate) fn check_read_only(connection: &DatabaseConnection, sql: &str) -> Result<(), String> {
+    if !connection.is_read_only() {
+        return Ok(());
+    }
+
+    let db_type = connection.db_type.as_str();
+    for statement in split_statements(sql, db_type) {
+        if !is_read_statement(&statement, db_type) {
+            let keyword = main_keyword(&statement, db_type);
+            return Err(format!("唯讀連接不能執行 {keyword} 語句"));
+        }
+    }
+    Ok(())
+}
+
+// 會寫入資料庫的功能（匯入、執行 SQL 檔案、維護操作）在唯讀連接上直接拒絕
+pub(crate) fn ensure_writable(connection: &DatabaseConnection, action: &str) -> Result<(), String> {
+    if connection.is_read_only() {
+        return Err(format!("唯讀連接不能{action}"));
+    }
+    Ok(())
+}
+
+// 匯出與 EXPLAIN ANALYZE 沒有確認流程，受保護環境中的高風險語句直接拒絕
+pub(crate) fn reject_risky(connection: &DatabaseConnection, sql: &str, action: &str) -> Result<(), String> {
+    if !connection.is_protected_environment() {
+        return Ok(());
+    }
+
+    let db_type = connection.db_type.as_str();
+    for statement in split_statements(sql, db_type) {
+        if let Some((keyword, reason)) = risk_reason(&statement, db_type) {
+            let environment = &connection.environment;
+            return Err(format!("{environment} 環境中{action}不能執行 {keyword} 語句（{reason}），請在查詢編輯器中確認後執行"));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskyStatement {
+    pub statement: String,
+    pub keyword: String,
+    pub reason: String,
+    pub table: Option<String>,
+    pub estimated_rows: Option<u64>, // 受影響表格目前的資料列數，無法估計時為 None
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmationRequired {
+    pub token: String, // 以 confirmation_token 重新送出相同的 SQL 才會執行
+    pub environment: String,
+    pub statements: Vec<RiskyStatement>,
+}
+
+// 已發出的確認 token，只能對同一個連接與 SQL 使用一次
+#[derive(Default)]
+pub(crate) struct PendingConfirmations(Mutex<HashMap<String, (String, Instant)>>);
+
+impl PendingConfirmations {
+    fn issue(&self, key: String) -> String {
+        let token = uuid::Uuid::new_v4().to_string();
+        let mut pending = self.0.lock().unwrap();
+        pending.retain(|_, (_, issued)| issued.elapsed() < CONFIRMATION_TTL);
+        pending.insert(token.clone(), (key, Instant::now()));
+        token
+    }
+
+    fn confirm(&self, token: &str, key: &str) -> bool {
+        let mut pending = self.0.lock().unwrap();
+        match pending.get(token) {
+            Some((issued_key, issued)) if issued_key == key && issued.elapsed() < CONFIRMATION_TTL => {
+                pending.remove(token);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn confirmation_key(connection: &DatabaseConnection, sql: &str) -> String {
+    let DatabaseConnection { db_type, host, port, database, username, .. } = connection;
+    format!("{db_type}\n{host}\n{port}\n{database}\n{username}\n{sql}")
+}
+
+// 在受保護環境中需要確認的語句，返回實際執行的關鍵字與原因
+// 沒有 WHERE 的 DELETE 與 UPDATE 可能出現在 WITH 的任何括號深度，WHERE 必須與它位於同一層
+fn risk_reason(sql: &str, db_type: &str) -> Option<(String, &'static str)> {
+    let words = statement_words(sql, db_type);
+    let keyword = main_keyword(sql, db_type);
+    let is_query = words.first().is_some_and(|(_, first)| first == "WITH") || STATEMENT_KEYWORDS.contains(&keyword.as_str());
+
+    if is_query {
+        for (i, (depth, word)) in words.iter().enumerate() {
+            // ON DELETE、FOR UPDATE、ON DUPLICATE KEY UPDATE 與 MERGE 的 THEN UPDATE 不是獨立的語句
+            if !matches!(word.as_str(), "DELETE" | "UPDATE") || (i > 0 && matches!(words[i - 1].1.as_str(), "ON" | "FOR" | "KEY" | "THEN")) {
+                continue;
+            }
+            let has_where = words[i + 1..]
+                .iter()
+                .take_while(|(inner, _)| inner >= depth)
+                .any(|(inner, word)| inner == depth && word == "WHERE");
+            if !has_where {
+                let reason = match word.as_str() {
+                    "DELETE" => "沒有 WHERE 條件，會刪除所有資料列",
+                    _ => "沒有 WHERE 條件，會更新所有資料列",
+                };
+                return Some((word.clone(), reason));
+            }
+        }
+        return None;
+    }
+
+    let reason = match keyword.as_str() {
+        "DROP" => "會刪除資料庫物件",
+        "TRUNCATE" => "會清空表格的所有資料",
+        "ALTER" => "會修改資料庫結構",
+        _ => return None,
+    };
+    Some((keyword, reason))
+}
+
+// 語句開頭的原始字詞，保留引號與 schema 前綴，遇到空白、逗號、分號或括號時分隔
+fn leading_tokens(sql: &str, db_type: &str, limit: usize) -> Vec<String> {
+    let Some(start) = statement_start(sql, db_type) else {
+        return vec![];
+    };
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quote: Option<char> = None;
+
+    for c in sql[start..].chars() {
+        match quote {
+            Some(close) => {
+                token.push(c);
+                if c == close {
+                    quote = None;
+                }
+            }
+            None if c.is_whitespace() || matches!(c, ',' | ';' | '(') => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                    if tokens.len() == limit {
+                        break;
+                    }
+                }
+                if c != ',' && !c.is_whitespace() {
+                    break;
+                }
+            }
+            None => {
+                quote = match c {
+                    '"' | '`' => Some(c),
+                    '[' => Some(']'),
+                    _ => None,
+                };
+                token.push(c);
+            }
+        }
+    }
+    if !token.is_empty() && tokens.len() < limit {
+        tokens.push(token);
+    }
+    tokens
+}
+
+// DELETE、UPDATE、TRUNCATE 與 DROP TABLE 影響的表格，WITH 開頭的語句不估計
+fn target_table(sql: &str, db_type: &str, keyword: &str) -> Option<String> {
+    let tokens = leading_tokens(sql, db_type, 8);
+    let first = tokens.first()?.to_uppercase();
+    if first != keyword {
+        return None;
+    }
+    if keyword == "DROP" && tokens.get(1).map(|token| token.to_uppercase()).as_deref() != Some("TABLE") {
+        return None;
+    }
+
+    const MODIFIERS: &[&str] = &["FROM", "TABLE", "ONLY", "IF", "EXISTS", "LOW_PRIORITY", "QUICK", "IGNORE", "OR", "REPLACE", "ROLLBACK", "ABORT", "FAIL"];
+    tokens
+        .into_iter()
+        .skip(1)
+        .find(|token| !MODIFIERS.contains(&token.to_uppercase().as_str()))
+        .filter(|token| token.chars().all(|c| !c.is_control() && c != '\''))
+}
+
+// 去掉識別字的引號，引號內的點不分隔
+fn table_name_parts(table: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quote: Option<char> = None;
+    let mut chars = table.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match quote {
+            // 連續兩個引號是跳脫
+            Some(close) if c == close && chars.peek() == Some(&close) && close != ']' => {
+                chars.next();
+                parts.last_mut().unwrap().push(c);
+            }
+            Some(close) if c == close => quote = None,
+            Some(_) => parts.last_mut().unwrap().push(c),
+            None => match c {
+                '"' | '`' => quote = Some(c),
+                '[' => quote = Some(']'),
+                '.' => parts.push(String::new()),
+                _ => parts.last_mut().unwrap().push(c),
+            },
+        }
+    }
+    parts
+}
+
+// 以系統目錄中的統計值估計，不掃描表格；表格名稱以參數綁定
+async fn estimate_rows(pool: &DbPool, db_type: &str, table: &str) -> Option<u64> {
+    let parts = table_name_parts(table);
+    let count = match (db_type, parts.as_slice()) {
+        // to_regclass 依 search_path 與原本的引號解析名稱，找不到時為 NULL；從未 ANALYZE 的表格是 -1
+        ("postgresql", _) => {
+            let sql = "SELECT reltuples::int8 FROM pg_class WHERE oid = to_regclass($1)";
+            pool.fetch_count(sql, &[Some(table)]).await
+        }
+        // InnoDB 的 TABLE_ROWS 是估計值，不是 COUNT(*)
+        ("mysql", [name]) | ("mysql", [_, name]) => {
+            let schema = (parts.len() == 2).then(|| parts[0].as_str());
+            let sql = "SELECT CAST(TABLE_ROWS AS SIGNED) FROM information_schema.TABLES WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ?";
+            pool.fetch_count(sql, &[schema, Some(name.as_str())]).await
+        }
+        // sqlite_stat1 只在執行過 ANALYZE 後存在，stat 的第一個數字是資料列數
+        ("sqlite", [name]) | ("sqlite", [_, name]) if parts.len() == 1 || parts[0].eq_ignore_ascii_case("main") => {
+            let sql = "SELECT CAST(stat AS INTEGER) FROM sqlite_stat1 WHERE tbl = ? LIMIT 1";
+            pool.fetch_count(sql, &[Some(name.as_str())]).await
+        }
+        _ => return None,
+    };
+    count.ok().flatten().and_then(|count| u64::try_from(count).ok())
+}
+
+// 受保護環境中的高風險語句需要確認；帶有效 token 時返回 None 直接執行
+pub(crate) async fn confirmation_required(
+    connection: &DatabaseConnection,
+    sql: &str,
+    token: Option<&str>,
+    confirmations: &PendingConfirmations,
+) -> Option<ConfirmationRequired> {
+    if !connection.is_protected_environment() {
+        return None;
+    }
+
+    let db_type = connection.db_type.as_str();
+    let mut statements: Vec<RiskyStatement> = split_statements(sql, db_type)
+        .into_iter()
+        .filter_map(|statement| {
+            let (keyword, reason) = risk_reason(&statement, db_type)?;
+            let table = target_table(&statement, db_type, &keyword);
+            Some(RiskyStatement { statement, keyword, reason: reason.to_string(), table, estimated_rows: None })
+        })
+        .collect();
+    if statements.is_empty() {
+        return None;
+    }
+
+    let key = confirmation_key(connection, sql);
+    if token.is_some_and(|token| confirmations.confirm(token, &key)) {
+        return None;
+    }
+
+    // 估計失敗不影響確認流程
+    if statements.iter().any(|statement| statement.table.is_some() && statement.keyword != "ALTER") {
+        if let Ok(pool) = DbPool::connect(connection).await {
+            for statement in statements.iter_mut().filter(|statement| statement.keyword != "ALTER") {
+                if let Some(table) = &statement.table {
+                    statement.estimated_rows = estimate_rows(&pool, db_type, table).await;
+                }
+            }
+            pool.close().await;
+        }
+    }
+
+    Some(ConfirmationRequired {
+        token: confirmations.issue(key),
+        environment: connection.environment.clone(),
+        statements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn main_keyword_cases() {
+        let cases = [
+            ("SELECT 1", "postgresql", "SELECT"),
+            ("  -- comment\n/* block */ delete from t", "postgresql", "DELETE"),
+            ("WITH x AS (SELECT 1) DELETE FROM t", "postgresql", "DELETE"),
+            ("WITH d AS (DELETE FROM t RETURNING 1) SELECT * FROM d", "postgresql", "SELECT"),
+            ("WITH RECURSIVE r(n) AS (VALUES (1)) SELECT n FROM r", "postgresql", "SELECT"),
+            ("EXPLAIN ANALYZE SELECT 1", "postgresql", "EXPLAIN"),
+            ("/*!40101 SET NAMES utf8 */", "mysql", "SET"),
+            ("", "sqlite", ""),
+        ];
+        for (sql, db_type, expected) in cases {
+            assert_eq!(main_keyword(sql, db_type), expected, "{sql}");
+        }
+    }
+
+    #[test]
+    fn read_statement_cases() {
+        let cases = [
+            ("SELECT * FROM t", "postgresql", true),
+            ("SELECT is_read_only FROM settings", "postgresql", true),
+            ("SELECT * FROM notes WHERE note = 'read_only'", "mysql", true),
+            ("SELECT * FROM t FOR UPDATE", "postgresql", true),
+            ("VALUES (1), (2)", "postgresql", true),
+            ("SHOW transaction_read_only", "postgresql", true),
+            ("SHOW TABLES", "mysql", true),
+            ("PRAGMA table_info(t)", "sqlite", true),
+            ("PRAGMA journal_mode = WAL", "sqlite", false),
+            ("PRAGMA writable_schema(1)", "sqlite", false),
+            ("BEGIN", "postgresql", true),
+            ("COMMIT", "postgresql", true),
+            ("BEGIN READ WRITE", "postgresql", false),
+            ("SET SESSION CHARACTERISTICS AS TRANSACTION READ WRITE", "postgresql", false),
+            ("SET search_path = app", "postgresql", true),
+            ("SET default_transaction_read_only = off", "postgresql", false),
+            ("SET \"default_transaction_read_only\" = off", "postgresql", false),
+            ("SET SESSION transaction_read_only = 0", "mysql", false),
+            ("SET GLOBAL max_connections = 10", "mysql", false),
+            ("SELECT set_config('default_transaction_read_only', 'off', false)", "postgresql", false),
+            ("RESET search_path", "postgresql", true),
+            ("RESET ALL", "postgresql", false),
+            ("RESET SESSION AUTHORIZATION", "postgresql", false),
+            ("RESET default_transaction_read_only", "postgresql", false),
+            ("RESET MASTER", "mysql", false),
+            ("DISCARD ALL", "postgresql", false),
+            ("SELECT * INTO backup FROM t", "postgresql", false),
+            ("SELECT a INTO OUTFILE '/tmp/a' FROM t", "mysql", false),
+            ("SELECT (SELECT 1 INTO x) FROM t", "postgresql", true),
+            ("WITH x AS (SELECT 1) SELECT * FROM x", "postgresql", true),
+            ("WITH d AS (DELETE FROM t RETURNING 1) SELECT * FROM d", "postgresql", false),
+            ("WITH u AS (UPDATE t SET a = 1 RETURNING a) SELECT * FROM u", "postgresql", false),
+            ("WITH x AS (SELECT 1) INSERT INTO t SELECT * FROM x", "postgresql", false),
+            ("EXPLAIN DELETE FROM t", "postgresql", true),
+            ("EXPLAIN ANALYZE SELECT * FROM t", "postgresql", true),
+            ("EXPLAIN ANALYZE DELETE FROM t", "postgresql", false),
+            ("EXPLAIN (ANALYZE, BUFFERS) UPDATE t SET a = 1", "postgresql", false),
+            ("EXPLAIN ANALYZE WITH d AS (DELETE FROM t RETURNING 1) SELECT * FROM d", "postgresql", false),
+            ("INSERT INTO t VALUES (1)", "sqlite", false),
+            ("DROP TABLE t", "mysql", false),
+            ("CALL p()", "mysql", false),
+        ];
+        for (sql, db_type, expected) in cases {
+            assert_eq!(is_read_statement(sql, db_type), expected, "{sql}");
+        }
+    }
+
+    #[test]
+    fn returns_rows_cases() {
+        let cases = [
+            ("SELECT 1", "postgresql", true),
+            ("  select * from t", "mysql", true),
+            ("(SELECT 1) UNION (SELECT 2)", "postgresql", true),
+            ("WITH x AS (SELECT 1 AS a) SELECT a FROM x", "postgresql", true),
+            ("WITH x AS (SELECT 1) DELETE FROM t", "postgresql", false),
+            ("VALUES (1, 'a')", "postgresql", true),
+            ("TABLE t", "postgresql", true),
+            ("SHOW TABLES", "mysql", true),
+            ("DESCRIBE t", "mysql", true),
+            ("EXPLAIN SELECT 1", "sqlite", true),
+            ("PRAGMA table_info(t)", "sqlite", true),
+            ("PRAGMA foreign_keys = ON", "sqlite", false),
+            ("SELECT * INTO backup FROM t", "postgresql", false),
+            ("INSERT INTO t VALUES (1) RETURNING id", "postgresql", true),
+            ("DELETE FROM t WHERE id IN (SELECT id FROM u)", "sqlite", false),
+            ("UPDATE t SET a = 1", "mysql", false),
+            ("CREATE TABLE t (a INT)", "sqlite", false),
+            ("SET search_path = app", "postgresql", false),
+        ];
+        for (sql, db_type, expected) in cases {
+            assert_eq!(returns_rows(sql, db_type), expected, "{sql}");
+        }
+    }
+
+    #[test]
+    fn risk_reason_cases() {
+        let cases = [
+            ("DELETE FROM t", "postgresql", Some("DELETE")),
+            ("DELETE FROM t WHERE id = 1", "postgresql", None),
+            ("UPDATE t SET a = 1", "mysql", Some("UPDATE")),
+            ("UPDATE t SET a = (SELECT b FROM u WHERE u.id = 1)", "mysql", Some("UPDATE")),
+            ("UPDATE t SET a = 1 WHERE id IN (SELECT id FROM u)", "sqlite", None),
+            ("WITH d AS (DELETE FROM t) SELECT 1", "postgresql", Some("DELETE")),
+            ("WITH d AS (DELETE FROM t WHERE id = 1 RETURNING *) SELECT * FROM d", "postgresql", None),
+            ("WITH d AS (DELETE FROM t RETURNING *) SELECT * FROM d WHERE id = 1", "postgresql", Some("DELETE")),
+            ("WITH u AS (UPDATE t SET a = 1 RETURNING a) SELECT * FROM u", "postgresql", Some("UPDATE")),
+            ("WITH x AS (SELECT 1) DELETE FROM t", "postgresql", Some("DELETE")),
+            ("SELECT * FROM t FOR UPDATE", "postgresql", None),
+            ("INSERT INTO t VALUES (1) ON DUPLICATE KEY UPDATE a = 1", "mysql", None),
+            ("INSERT INTO t SELECT * FROM u", "postgresql", None),
+            ("CREATE TABLE c (p INT REFERENCES p ON DELETE CASCADE)", "postgresql", None),
+            ("DROP TABLE t", "mysql", Some("DROP")),
+            ("TRUNCATE TABLE t", "postgresql", Some("TRUNCATE")),
+            ("ALTER TABLE t ADD COLUMN c INT", "sqlite", Some("ALTER")),
+            ("SELECT 'DELETE FROM t'", "postgresql", None),
+        ];
+        for (sql, db_type, expected) in cases {
+            let keyword = risk_reason(sql, db_type).map(|(keyword, _)| keyword);
+            assert_eq!(keyword.as_deref(), expected, "{sql}");
+        }
+    }
+
+    #[test]
+    fn table_name_parts_cases() {
+        assert_eq!(table_name_parts("orders"), ["orders"]);
+        assert_eq!(table_name_parts("app.orders"), ["app", "orders"]);
+        assert_eq!(table_name_parts("`app`.`order.items`"), ["app", "order.items"]);
+        assert_eq!(table_name_parts("\"My \"\"Table\"\""), ["My \"Table\""]);
+        assert_eq!(table_name_parts("[main].[t]"), ["main", "t"]);
+    }
+}
@@ -0,0 +1,413 @@
+// 切分出的語句與其在原始文字中的起始行號（從 1 開始）
+#[derive(Debug, Clone)]
+pub(crate) struct SplitStatement {
+    pub line: usize,
+    pub sql: String,
+}
+
+// 依方言規則切分 SQL 語句，可逐段餵入文字以支援大型檔案
+// 逐段餵入時每段需以完整的行為單位，避免切斷註解或字串的起訖符號
+pub(crate) struct StatementSplitter {
+    db_type: String,
+    buffer: String,
+    buffer_line: usize,
+    delimiter: Vec<char>,
+    state: LexState,
+    block_depth: usize, // SQLite 觸發器中尚未以 END 結束的 BEGIN 與 CASE 數量
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum LexState {
+    Normal,
+    SingleQuote { backslash: bool }, // backslash 為 true 時反斜線會跳脫下一個字元
+    DoubleQuote { backslash: bool },
+    Backtick,
+    Bracket,
+    LineComment,
+    BlockComment(usize),
+    DollarQuote(String),
+}
+
+impl StatementSplitter {
+    pub(crate) fn new(db_type: &str) -> Self {
+        Self {
+            db_type: db_type.to_string(),
+            buffer: String::new(),
+            buffer_line: 1,
+            delimiter: vec![';'],
+            state: LexState::Normal,
+            block_depth: 0,
+        }
+    }
+
+    // 餵入一段文字，返回已完整結束的語句
+    pub(crate) fn push(&mut self, chunk: &str) -> Vec<SplitStatement> {
+        let mut statements = Vec::new();
+        let chars: Vec<char> = chunk.chars().collect();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+
+            // mysql 用戶端的 DELIMITER 指令（mysqldump 匯出觸發器與預存程序時使用），只出現在行首
+            let line_start = i == 0 || chars[i - 1] == '\n';
+            if line_start && self.state == LexState::Normal && self.db_type == "mysql" && self.buffer_is_blank() {
+                let line_end = chars[i..].iter().position(|&c| c == '\n').map_or(chars.len(), |end| i + end + 1);
+                if let Some(delimiter) = delimiter_command(&chars[i..line_end]) {
+                    self.buffer_line += self.buffer.matches('\n').count() + 1;
+                    self.buffer.clear();
+                    self.delimiter = delimiter;
+                    i = line_end;
+                    continue;
+                }
+            }
+
+            match self.state.clone() {
+                LexState::Normal if chars[i..].starts_with(&self.delimiter) && !self.inside_trigger_body() => {
+                    if let Some(statement) = self.take_statement() {
+                        statements.push(statement);
+                    }
+                    i += self.delimiter.len();
+                    continue;
+                }
+                LexState::Normal => match c {
+                    // MySQL 字串與 PostgreSQL 的 E'...' 字串以反斜線跳脫
+                    '\'' => {
+                        let backslash = self.db_type == "mysql" || (self.db_type == "postgresql" && self.ends_with_escape_prefix());
+                        self.state = LexState::SingleQuote { backslash };
+                    }
+                    // MySQL 預設把雙引號視為字串
+                    '"' => self.state = LexState::DoubleQuote { backslash: self.db_type == "mysql" },
+                    '`' if self.db_type == "mysql" => self.state = LexState::Backtick,
+                    '[' if self.db_type == "sqlite" => self.state = LexState::Bracket,
+                    c if self.db_type == "sqlite" && (c.is_alphabetic() || c == '_') && !self.ends_with_word_char() => {
+                        let word: String = chars[i..]
+                            .iter()
+                            .take_while(|c| c.is_alphanumeric() || **c == '_' || **c == '$')
+                            .collect();
+                        match word.to_uppercase().as_str() {
+                            "BEGIN" | "CASE" => self.block_depth += 1,
+                            "END" => self.block_depth = self.block_depth.saturating_sub(1),
+                            _ => {}
+                        }
+                    }
+                    '#' if self.db_type == "mysql" => self.state = LexState::LineComment,
+                    '-' if next == Some('-') && dashes_start_comment(chars.get(i + 2).copied(), &self.db_type) => {
+                        self.state = LexState::LineComment;
+                    }
+                    '/' if next == Some('*') => {
+                        self.buffer.push_str("/*");
+                        self.state = LexState::BlockComment(1);
+                        i += 2;
+                        continue;
+                    }
+                    '$' if self.db_type == "postgresql" => {
+                        // PostgreSQL 的 $tag$ ... $tag$ 字串
+                        if let Some(tag) = dollar_tag(&chars[i..]) {
+                            self.buffer.push_str(&tag);
+                            i += tag.chars().count();
+                            self.state = LexState::DollarQuote(tag);
+                            continue;
+                        }
+                    }
+                    _ => {}
+                },
+                LexState::SingleQuote { backslash } | LexState::DoubleQuote { backslash } if backslash && c == '\\' => {
+                    self.buffer.push(c);
+                    if let Some(escaped) = next {
+                        self.buffer.push(escaped);
+                    }
+                    i += 2;
+                    continue;
+                }
+                LexState::SingleQuote { .. } => {
+                    if c == '\'' {
+                        self.state = LexState::Normal;
+                    }
+                }
+                LexState::DoubleQuote { .. } => {
+                    if c == '"' {
+                        self.state = LexState::Normal;
+                    }
+                }
+                LexState::Bracket => {
+                    if c == ']' {
+                        self.state = LexState::Normal;
+                    }
+                }
+                LexState::Backtick => {
+                    if c == '`' {
+                        self.state = LexState::Normal;
+                    }
+                }
+                LexState::LineComment => {
+                    if c == '\n' {
+                        self.state = LexState::Normal;
+                    }
+                }
+                LexState::BlockComment(depth) => {
+                    if c == '*' && next == Some('/') {
+                        self.buffer.push_str("*/");
+                        self.state = if depth > 1 { LexState::BlockComment(depth - 1) } else { LexState::Normal };
+                        i += 2;
+                        continue;
+                    }
+                    // PostgreSQL 支援巢狀區塊註解
+                    if c == '/' && next == Some('*') && self.db_type == "postgresql" {
+                        self.buffer.push_str("/*");
+                        self.state = LexState::BlockComment(depth + 1);
+                        i += 2;
+                        continue;
+                    }
+                }
+                LexState::DollarQuote(tag) => {
+                    if c == '$' && chars[i..].iter().take(tag.chars().count()).collect::<String>() == tag {
+                        self.buffer.push_str(&tag);
+                        i += tag.chars().count();
+                        self.state = LexState::Normal;
+                        continue;
+                    }
+                }
+            }
+
+            self.buffer.push(c);
+            i += 1;
+        }
+
+        statements
+    }
+
+    // 輸入結束，返回最後一條沒有分號結尾的語句
+    pub(crate) fn finish(&mut self) -> Option<SplitStatement> {
+        self.state = LexState::Normal;
+        self.take_statement()
+    }
+
+    // 緩衝區以 E 或 e 單獨結尾時，接下來的字串是 PostgreSQL 的跳脫字串
+    fn ends_with_escape_prefix(&self) -> bool {
+        let mut chars = self.buffer.chars().rev();
+        matches!(chars.next(), Some('E' | 'e'))
+            && !chars.next().is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$')
+    }
+
+    fn ends_with_word_char(&self) -> bool {
+        self.buffer.chars().next_back().is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$')
+    }
+
+    // SQLite 的 CREATE TRIGGER 內含以分號結尾的語句，直到與 BEGIN 對應的 END 才結束
+    // 主體中的 CASE ... END 也以 END 結尾，因此計算尚未結束的 BEGIN 與 CASE
+    fn inside_trigger_body(&self) -> bool {
+        if self.db_type != "sqlite" || statement_keyword(&self.buffer, &self.db_type) != "CREATE" {
+            return false;
+        }
+        let Some(start) = statement_start(&self.buffer, &self.db_type) else {
+            return false;
+        };
+        let words: Vec<String> = self.buffer[start..]
+            .split_whitespace()
+            .take(3)
+            .map(|word| word.to_uppercase())
+            .collect();
+        let is_trigger = words.get(1).map(String::as_str) == Some("TRIGGER")
+            || (matches!(words.get(1).map(String::as_str), Some("TEMP" | "TEMPORARY"))
+                && words.get(2).map(String::as_str) == Some("TRIGGER"));
+        is_trigger && self.block_depth > 0
+    }
+
+    fn buffer_is_blank(&self) -> bool {
+        statement_start(&self.buffer, &self.db_type).is_none()
+    }
+
+    fn take_statement(&mut self) -> Option<SplitStatement> {
+        self.block_depth = 0;
+        let statement = std::mem::take(&mut self.buffer);
+        let start_line = self.buffer_line;
+        self.buffer_line += statement.matches('\n').count();
+
+        // 行號指向第一個非註解的字元
+        let start = statement_start(&statement, &self.db_type)?;
+        Some(SplitStatement {
+            line: start_line + statement[..start].matches('\n').count(),
+            sql: statement.trim().to_string(),
+        })
+    }
+}
+
+// 一次切分完整的 SQL 文字
+pub(crate) fn split_statements(sql: &str, db_type: &str) -> Vec<String> {
+    let mut splitter = StatementSplitter::new(db_type);
+    let mut statements = splitter.push(sql);
+    statements.extend(splitter.finish());
+    statements.into_iter().map(|statement| statement.sql).collect()
+}
+
+// MySQL 的 -- 之後必須是空白、控制字元或輸入結尾才是註解，SELECT 1--1 是減去負數
+pub(crate) fn dashes_start_comment(after: Option<char>, db_type: &str) -> bool {
+    db_type != "mysql" || after.is_none_or(|c| c.is_whitespace() || c.is_control())
+}
+
+// 返回第一個非空白、非註解字元的位置，只有空白或註解的片段不算語句
+// MySQL 的 /*! ... */ 是會被執行的條件註解
+pub(crate) fn statement_start(text: &str, db_type: &str) -> Option<usize> {
+    let mut offset = 0;
+    loop {
+        let rest = &text[offset..];
+        let trimmed = rest.trim_start();
+        offset += rest.len() - trimmed.len();
+
+        if trimmed.is_empty() {
+            return None;
+        }
+        let dashes = trimmed.starts_with("--") && dashes_start_comment(trimmed[2..].chars().next(), db_type);
+        if dashes || (db_type == "mysql" && trimmed.starts_with('#')) {
+            offset += trimmed.find('\n')? + 1;
+        } else if trimmed.starts_with("/*") && !(db_type == "mysql" && trimmed.starts_with("/*!")) {
+            offset += trimmed[2..].find("*/")? + 4;
+        } else {
+            return Some(offset);
+        }
+    }
+}
+
+// 語句的第一個關鍵字（大寫），略過開頭的註解與 MySQL 條件註解的版本號
+pub(crate) fn statement_keyword(sql: &str, db_type: &str) -> String {
+    let Some(start) = statement_start(sql, db_type) else {
+        return String::new();
+    };
+    let mut rest = &sql[start..];
+    if let Some(conditional) = rest.strip_prefix("/*!") {
+        rest = conditional.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start();
+    }
+    rest.chars()
+        .take_while(|c| c.is_alphabetic() || *c == '_')
+        .collect::<String>()
+        .to_uppercase()
+}
+
+// 解析 DELIMITER 指令，返回新的分隔符號
+fn delimiter_command(line: &[char]) -> Option<Vec<char>> {
+    let line: String = line.iter().collect();
+    let line = line.trim();
+    let keyword = line.get(..9)?;
+    if !keyword.eq_ignore_ascii_case("delimiter") {
+        return None;
+    }
+    let delimiter = line[9..].trim();
+    if delimiter.is_empty() || !line[9..].starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(delimiter.chars().collect())
+}
+
+// 解析 $tag$ 開頭，tag 只能包含字母、數字與底線
+fn dollar_tag(chars: &[char]) -> Option<String> {
+    let mut tag = String::from("$");
+    for (index, &c) in chars.iter().enumerate().skip(1) {
+        if c == '$' {
+            tag.push('$');
+            return Some(tag);
+        }
+        let valid = c == '_' || c.is_alphabetic() || (index > 1 && c.is_ascii_digit());
+        if !valid {
+            return None;
+        }
+        tag.push(c);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_semicolons_outside_strings_and_comments() {
+        let sql = "SELECT 'a;b'; -- c;d\nSELECT \"e;f\"; /* g; */ SELECT 1";
+        assert_eq!(split_statements(sql, "postgresql"), vec!["SELECT 'a;b'", "-- c;d\nSELECT \"e;f\"", "/* g; */ SELECT 1"]);
+    }
+
+    #[test]
+    fn sqlite_bracketed_identifiers() {
+        let sql = "SELECT [a;b] FROM t; SELECT 2";
+        assert_eq!(split_statements(sql, "sqlite"), vec!["SELECT [a;b] FROM t", "SELECT 2"]);
+    }
+
+    #[test]
+    fn postgres_escape_strings() {
+        let sql = r"SELECT E'it\'s;'; SELECT e'\\'; SELECT 'plain\'; SELECT 4";
+        assert_eq!(
+            split_statements(sql, "postgresql"),
+            vec![r"SELECT E'it\'s;'", r"SELECT e'\\'", r"SELECT 'plain\'", "SELECT 4"]
+        );
+        // 識別字結尾的 E 不是跳脫字串的前綴
+        assert_eq!(split_statements(r"SELECT name'\'; SELECT 2", "postgresql"), vec![r"SELECT name'\'", "SELECT 2"]);
+    }
+
+    #[test]
+    fn mysql_backslash_escapes() {
+        let sql = r#"SELECT "a\";b"; SELECT 'c\';d'; SELECT 3"#;
+        assert_eq!(split_statements(sql, "mysql"), vec![r#"SELECT "a\";b""#, r"SELECT 'c\';d'", "SELECT 3"]);
+        // PostgreSQL 的雙引號識別字沒有反斜線跳脫
+        assert_eq!(split_statements(r#"SELECT 1 AS "a\"; SELECT 2"#, "postgresql"), vec![r#"SELECT 1 AS "a\""#, "SELECT 2"]);
+    }
+
+    #[test]
+    fn mysql_double_dash_needs_whitespace() {
+        let sql = "SELECT 1--1; UPDATE t SET a=a--1; SELECT 2 -- c;d\nFROM t;--\nSELECT 3";
+        assert_eq!(
+            split_statements(sql, "mysql"),
+            vec!["SELECT 1--1", "UPDATE t SET a=a--1", "SELECT 2 -- c;d\nFROM t", "--\nSELECT 3"]
+        );
+        // 其他資料庫的 -- 不需要空白
+        assert_eq!(split_statements("SELECT 1--1;\nSELECT 2", "postgresql"), vec!["SELECT 1--1;\nSELECT 2"]);
+    }
+
+    #[test]
+    fn sqlite_trigger_body_with_case() {
+        let sql = "CREATE TRIGGER trg AFTER INSERT ON t BEGIN\n\
+                   UPDATE t SET a = CASE WHEN new.b THEN 1 ELSE 0 END;\n\
+                   SELECT CASE new.c WHEN 1 THEN 'x' END;\n\
+                   END;\n\
+                   SELECT 1;";
+        let statements = split_statements(sql, "sqlite");
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].ends_with("END"));
+        assert_eq!(statements[1], "SELECT 1");
+    }
+
+    #[test]
+    fn mysql_delimiter_command() {
+        let sql = "DELIMITER //\nCREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END//\nDELIMITER ;\nCALL p();\nSELECT 3";
+        let mut splitter = StatementSplitter::new("mysql");
+        let mut split = splitter.push(sql);
+        split.extend(splitter.finish());
+        let texts: Vec<&str> = split.iter().map(|statement| statement.sql.as_str()).collect();
+        assert_eq!(texts, vec!["CREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END", "CALL p()", "SELECT 3"]);
+        assert_eq!(split.iter().map(|statement| statement.line).collect::<Vec<_>>(), vec![2, 4, 5]);
+    }
+
+    #[test]
+    fn postgres_dollar_quotes() {
+        let sql = "CREATE FUNCTION f() RETURNS int AS $body$ BEGIN RETURN 1; END; $body$ LANGUAGE plpgsql; SELECT $$a;b$$";
+        assert_eq!(
+            split_statements(sql, "postgresql"),
+            vec!["CREATE FUNCTION f() RETURNS int AS $body$ BEGIN RETURN 1; END; $body$ LANGUAGE plpgsql", "SELECT $$a;b$$"]
+        );
+        // $1 參數不是字串的開頭
+        assert_eq!(split_statements("SELECT $1; SELECT 2", "postgresql"), vec!["SELECT $1", "SELECT 2"]);
+    }
+
+    #[test]
+    fn nested_block_comments() {
+        let sql = "/* a /* b; */ c; */ SELECT 1; SELECT 2";
+        assert_eq!(split_statements(sql, "postgresql"), vec!["/* a /* b; */ c; */ SELECT 1", "SELECT 2"]);
+        // MySQL 不支援巢狀註解，第一個 */ 就結束註解
+        assert_eq!(split_statements("/* a /* b */ SELECT 1; SELECT 2", "mysql"), vec!["/* a /* b */ SELECT 1", "SELECT 2"]);
+    }
+
+    #[test]
+    fn comment_only_fragments_are_not_statements() {
+        assert!(split_statements("-- only a comment\n; /* x */ ;", "sqlite").is_empty());
+    }
+}
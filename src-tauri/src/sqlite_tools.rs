@@ -0,0 +1,472 @@
+use std::ffi::{CStr, CString};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use libsqlite3_sys as ffi;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::connection::{connect_sqlite, sqlite_database_path};
+use crate::dialect::{quote_identifier, quote_string};
+use crate::sql_guard::ensure_writable;
+use crate::DatabaseConnection;
+
+// 線上備份每一步複製的頁數
+const BACKUP_PAGES_PER_STEP: i32 = 256;
+// VACUUM INTO 期間檢查目標檔案大小的間隔
+const VACUUM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SqliteMaintenanceRequest {
+    pub connection: DatabaseConnection,
+    // 'backup', 'vacuum', 'vacuum_into', 'integrity_check', 'quick_check', 'foreign_key_check', 'analyze', 'checkpoint'
+    pub operation: String,
+    pub target_path: Option<String>, // 備份與 VACUUM INTO 的目標檔案，未指定時開啟儲存對話框
+    pub table_name: Option<String>, // ANALYZE 與 foreign_key_check 只處理指定表格
+    pub checkpoint_mode: Option<String>, // 'passive', 'full', 'restart', 'truncate'
+    pub max_errors: Option<u32>, // integrity_check 與 quick_check 最多返回的問題數
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SqliteCheckIssue {
+    pub table: Option<String>,
+    pub row_id: Option<i64>,
+    pub parent: Option<String>,
+    pub foreign_key_id: Option<i64>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalCheckpoint {
+    pub busy: bool,
+    pub log_frames: i64,
+    pub checkpointed_frames: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SqliteMaintenanceResult {
+    pub success: bool,
+    pub operation: String,
+    pub size_before: Option<u64>,
+    pub size_after: Option<u64>,
+    pub wal_size_before: Option<u64>,
+    pub wal_size_after: Option<u64>,
+    pub target_path: Option<String>,
+    pub target_size: Option<u64>,
+    pub issues: Vec<SqliteCheckIssue>,
+    pub checkpoint: Option<WalCheckpoint>,
+    pub execution_time: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SqliteMaintenanceProgress {
+    pub operation: String,
+    pub database: String,
+    pub stage: String, // 'started', 'running', 'finished'
+    pub done: Option<u64>, // 備份為頁數，VACUUM INTO 為目標檔案大小
+    pub total: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct MaintenanceOutcome {
+    target_path: Option<String>,
+    issues: Vec<SqliteCheckIssue>,
+    checkpoint: Option<WalCheckpoint>,
+}
+
+#[tauri::command]
+pub async fn sqlite_maintenance(app: AppHandle, request: SqliteMaintenanceRequest) -> Result<SqliteMaintenanceResult, String> {
+    let start_time = std::time::Instant::now();
+    if request.connection.db_type != "sqlite" {
+        return Err("此功能只支援 SQLite".to_string());
+    }
+
+    let database_path = sqlite_database_path(&request.connection);
+    let wal_path = format!("{database_path}-wal");
+    let size_before = file_size(&database_path);
+    let wal_size_before = file_size(&wal_path);
+
+    let progress = ProgressEmitter {
+        app: app.clone(),
+        operation: request.operation.clone(),
+        database: database_path.clone(),
+    };
+    progress.emit("started", None, None);
+
+    let mut outcome = MaintenanceOutcome::default();
+    let result = run_operation(&app, &request, &database_path, &progress, &mut outcome).await;
+    progress.emit("finished", None, None);
+
+    let size_after = file_size(&database_path);
+    let target_size = outcome.target_path.as_deref().and_then(file_size);
+    let execution_time = start_time.elapsed().as_millis() as u64;
+
+    let message = match &result {
+        Ok(Some(message)) => message.clone(),
+        Ok(None) => "已取消操作".to_string(),
+        Err(error) => format!("操作失敗: {error}"),
+    };
+
+    Ok(SqliteMaintenanceResult {
+        success: matches!(result, Ok(Some(_))),
+        operation: request.operation.clone(),
+        size_before,
+        size_after,
+        wal_size_before,
+        wal_size_after: file_size(&wal_path),
+        target_path: outcome.target_path,
+        target_size,
+        issues: outcome.issues,
+        checkpoint: outcome.checkpoint,
+        execution_time,
+        message,
+    })
+}
+
+struct ProgressEmitter {
+    app: AppHandle,
+    operation: String,
+    database: String,
+}
+
+impl ProgressEmitter {
+    fn emit(&self, stage: &str, done: Option<u64>, total: Option<u64>) {
+        let _ = self.app.emit("sqlite-maintenance-progress", SqliteMaintenanceProgress {
+            operation: self.operation.clone(),
+            database: self.database.clone(),
+            stage: stage.to_string(),
+            done,
+            total,
+        });
+    }
+}
+
+fn file_size(path: &str) -> Option<u64> {
+    if path == ":memory:" {
+        return None;
+    }
+    std::fs::metadata(path).ok().map(|metadata| metadata.len())
+}
+
+// 執行指定的維護操作，返回結果訊息；使用者取消選擇檔案時返回 None
+async fn run_operation(
+    app: &AppHandle,
+    request: &SqliteMaintenanceRequest,
+    database_path: &str,
+    progress: &ProgressEmitter,
+    outcome: &mut MaintenanceOutcome,
+) -> Result<Option<String>, String> {
+    let operation = request.operation.as_str();
+    if !matches!(
+        operation,
+        "backup" | "vacuum" | "vacuum_into" | "integrity_check" | "quick_check" | "foreign_key_check" | "analyze" | "checkpoint"
+    ) {
+        return Err(format!("不支援的維護操作: {operation}"));
+    }
+    // 備份、VACUUM INTO 與檢查只讀取來源資料庫
+    if matches!(operation, "vacuum" | "analyze" | "checkpoint") {
+        ensure_writable(&request.connection, &format!("執行 {operation}"))?;
+    }
+
+    if operation == "backup" || operation == "vacuum_into" {
+        if database_path == ":memory:" {
+            return Err("內存資料庫無法備份".to_string());
+        }
+        // 線上備份直接開啟檔案，無法套用 SQLCipher 密鑰
+        if operation == "backup" && request.connection.encryption_key.as_deref().is_some_and(|key| !key.is_empty()) {
+            return Err("加密資料庫請使用 VACUUM INTO 備份".to_string());
+        }
+        let target = match &request.target_path {
+            Some(path) if !path.is_empty() => PathBuf::from(path),
+            _ => match choose_target_path(app, database_path).await? {
+                Some(path) => path,
+                None => return Ok(None),
+            },
+        };
+        if target == Path::new(database_path) {
+            return Err("目標檔案不能是來源資料庫".to_string());
+        }
+        outcome.target_path = Some(target.to_string_lossy().into_owned());
+    }
+
+    let pool = connect_sqlite(&request.connection).await?;
+    let result = match operation {
+        "backup" => backup(database_path, outcome.target_path.clone().unwrap_or_default(), progress).await,
+        "vacuum" => vacuum(&pool).await,
+        "vacuum_into" => vacuum_into(&pool, database_path, outcome.target_path.as_deref().unwrap_or_default(), progress).await,
+        "integrity_check" | "quick_check" => integrity_check(&pool, operation, request.max_errors, outcome).await,
+        "foreign_key_check" => foreign_key_check(&pool, request.table_name.as_deref(), outcome).await,
+        "analyze" => analyze(&pool, request.table_name.as_deref()).await,
+        _ => checkpoint(&pool, request.checkpoint_mode.as_deref(), outcome).await,
+    };
+    pool.close().await;
+    result.map(Some)
+}
+
+async fn choose_target_path(app: &AppHandle, database_path: &str) -> Result<Option<PathBuf>, String> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    let stem = Path::new(database_path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "database".to_string());
+
+    app.dialog()
+        .file()
+        .add_filter("SQLite", &["db", "sqlite", "sqlite3"])
+        .set_file_name(format!("{stem}-backup.db"))
+        .save_file(move |path| {
+            let _ = sender.send(path);
+        });
+
+    match receiver.await.map_err(|e| format!("儲存對話框錯誤: {e}"))? {
+        Some(path) => path.into_path().map(Some).map_err(|e| format!("無效的檔案路徑: {e}")),
+        None => Ok(None),
+    }
+}
+
+async fn backup(database_path: &str, target: String, progress: &ProgressEmitter) -> Result<String, String> {
+    let source = database_path.to_string();
+    let app = progress.app.clone();
+    let step_progress = SqliteMaintenanceProgress {
+        operation: progress.operation.clone(),
+        database: progress.database.clone(),
+        stage: "running".to_string(),
+        done: None,
+        total: None,
+    };
+
+    let target_path = target.clone();
+    tokio::task::spawn_blocking(move || {
+        online_backup(&source, &target_path, |done, total| {
+            let _ = app.emit("sqlite-maintenance-progress", SqliteMaintenanceProgress {
+                done: Some(done),
+                total: Some(total),
+                ..step_progress.clone()
+            });
+        })
+    })
+    .await
+    .map_err(|e| format!("備份執行錯誤: {e}"))??;
+
+    Ok(format!("備份完成: {target}"))
+}
+
+// SQLite 連接的原始控制代碼，離開作用域時關閉
+struct RawDatabase(*mut ffi::sqlite3);
+
+impl RawDatabase {
+    fn open(path: &str, flags: i32) -> Result<Self, String> {
+        let c_path = CString::new(path).map_err(|_| "檔案路徑包含無效字元".to_string())?;
+        let mut handle = std::ptr::null_mut();
+        let rc = unsafe { ffi::sqlite3_open_v2(c_path.as_ptr(), &mut handle, flags, std::ptr::null()) };
+        // 開啟失敗時仍需要關閉控制代碼
+        let database = Self(handle);
+        if rc != ffi::SQLITE_OK {
+            return Err(format!("無法開啟 {path}: {}", database.error_message()));
+        }
+        Ok(database)
+    }
+
+    fn error_message(&self) -> String {
+        unsafe { CStr::from_ptr(ffi::sqlite3_errmsg(self.0)) }.to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for RawDatabase {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::sqlite3_close(self.0);
+        }
+    }
+}
+
+// 使用 SQLite 線上備份 API 分段複製頁面，備份期間其他連接仍可存取來源資料庫
+fn online_backup(source: &str, target: &str, mut on_step: impl FnMut(u64, u64)) -> Result<(), String> {
+    let source_db = RawDatabase::open(source, ffi::SQLITE_OPEN_READONLY)?;
+    let target_db = RawDatabase::open(target, ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE)?;
+
+    let main = c"main";
+    let backup = unsafe { ffi::sqlite3_backup_init(target_db.0, main.as_ptr(), source_db.0, main.as_ptr()) };
+    if backup.is_null() {
+        return Err(format!("初始化備份錯誤: {}", target_db.error_message()));
+    }
+
+    loop {
+        let rc = unsafe { ffi::sqlite3_backup_step(backup, BACKUP_PAGES_PER_STEP) };
+        let (remaining, total) = unsafe { (ffi::sqlite3_backup_remaining(backup), ffi::sqlite3_backup_pagecount(backup)) };
+        on_step((total - remaining).max(0) as u64, total.max(0) as u64);
+
+        match rc {
+            ffi::SQLITE_OK => {}
+            ffi::SQLITE_DONE => break,
+            // 來源正被其他連接寫入時稍後重試
+            ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED => std::thread::sleep(Duration::from_millis(50)),
+            _ => {
+                unsafe { ffi::sqlite3_backup_finish(backup) };
+                return Err(format!("備份錯誤: {}", target_db.error_message()));
+            }
+        }
+    }
+
+    let rc = unsafe { ffi::sqlite3_backup_finish(backup) };
+    if rc != ffi::SQLITE_OK {
+        return Err(format!("備份錯誤: {}", target_db.error_message()));
+    }
+    Ok(())
+}
+
+async fn vacuum(pool: &sqlx::SqlitePool) -> Result<String, String> {
+    sqlx::query("VACUUM")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("VACUUM 錯誤: {e}"))?;
+    Ok("VACUUM 完成".to_string())
+}
+
+async fn vacuum_into(
+    pool: &sqlx::SqlitePool,
+    database_path: &str,
+    target: &str,
+    progress: &ProgressEmitter,
+) -> Result<String, String> {
+    if Path::new(target).exists() {
+        return Err(format!("目標檔案已存在: {target}"));
+    }
+
+    let sql = format!("VACUUM INTO {}", quote_string("sqlite", target));
+    let vacuum = sqlx::query(&sql).execute(pool);
+    tokio::pin!(vacuum);
+
+    // VACUUM INTO 沒有進度回報，以目標檔案大小對比來源大小估計進度
+    let total = file_size(database_path);
+    let mut ticker = tokio::time::interval(VACUUM_POLL_INTERVAL);
+    let result = loop {
+        tokio::select! {
+            result = &mut vacuum => break result,
+            _ = ticker.tick() => progress.emit("running", file_size(target), total),
+        }
+    };
+    result.map_err(|e| format!("VACUUM INTO 錯誤: {e}"))?;
+
+    Ok(format!("已壓縮複製到 {target}"))
+}
+
+async fn integrity_check(
+    pool: &sqlx::SqlitePool,
+    operation: &str,
+    max_errors: Option<u32>,
+    outcome: &mut MaintenanceOutcome,
+) -> Result<String, String> {
+    let sql = format!("PRAGMA {operation}({})", max_errors.unwrap_or(100).max(1));
+    let messages: Vec<String> = sqlx::query_scalar(&sql)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("{operation} 錯誤: {e}"))?;
+
+    // 沒有問題時只返回一行 ok
+    outcome.issues = messages
+        .into_iter()
+        .filter(|message| message != "ok")
+        .map(|message| SqliteCheckIssue {
+            table: None,
+            row_id: None,
+            parent: None,
+            foreign_key_id: None,
+            message,
+        })
+        .collect();
+
+    Ok(match outcome.issues.len() {
+        0 => "檢查完成，沒有發現問題".to_string(),
+        count => format!("檢查完成，發現 {count} 個問題"),
+    })
+}
+
+async fn foreign_key_check(
+    pool: &sqlx::SqlitePool,
+    table_name: Option<&str>,
+    outcome: &mut MaintenanceOutcome,
+) -> Result<String, String> {
+    let sql = match table_name {
+        Some(table) if !table.is_empty() => format!("PRAGMA foreign_key_check({})", quote_identifier("sqlite", table)),
+        _ => "PRAGMA foreign_key_check".to_string(),
+    };
+    let rows = sqlx::query(&sql)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("foreign_key_check 錯誤: {e}"))?;
+
+    for row in &rows {
+        let table: String = row.try_get(0).map_err(|e| format!("取得表格名稱錯誤: {e}"))?;
+        // WITHOUT ROWID 表格的 rowid 為 NULL
+        let row_id: Option<i64> = row.try_get(1).unwrap_or(None);
+        let parent: String = row.try_get(2).map_err(|e| format!("取得參照表格錯誤: {e}"))?;
+        let foreign_key_id: i64 = row.try_get(3).unwrap_or_default();
+
+        let message = match row_id {
+            Some(row_id) => format!("{table} 的第 {row_id} 行參照 {parent} 中不存在的資料"),
+            None => format!("{table} 有資料參照 {parent} 中不存在的資料"),
+        };
+        outcome.issues.push(SqliteCheckIssue {
+            table: Some(table),
+            row_id,
+            parent: Some(parent),
+            foreign_key_id: Some(foreign_key_id),
+            message,
+        });
+    }
+
+    Ok(match outcome.issues.len() {
+        0 => "外鍵檢查完成，沒有發現問題".to_string(),
+        count => format!("外鍵檢查完成，發現 {count} 筆違反外鍵的資料"),
+    })
+}
+
+async fn analyze(pool: &sqlx::SqlitePool, table_name: Option<&str>) -> Result<String, String> {
+    let sql = match table_name {
+        Some(table) if !table.is_empty() => format!("ANALYZE {}", quote_identifier("sqlite", table)),
+        _ => "ANALYZE".to_string(),
+    };
+    sqlx::query(&sql)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("ANALYZE 錯誤: {e}"))?;
+    Ok("ANALYZE 完成".to_string())
+}
+
+async fn checkpoint(
+    pool: &sqlx::SqlitePool,
+    mode: Option<&str>,
+    outcome: &mut MaintenanceOutcome,
+) -> Result<String, String> {
+    let mode = mode.unwrap_or("passive").to_uppercase();
+    if !matches!(mode.as_str(), "PASSIVE" | "FULL" | "RESTART" | "TRUNCATE") {
+        return Err(format!("不支援的檢查點模式: {mode}"));
+    }
+
+    let row = sqlx::query(&format!("PRAGMA wal_checkpoint({mode})"))
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("wal_checkpoint 錯誤: {e}"))?;
+    let busy: i64 = row.try_get(0).unwrap_or_default();
+    let log_frames: i64 = row.try_get(1).unwrap_or(-1);
+    let checkpointed_frames: i64 = row.try_get(2).unwrap_or(-1);
+
+    outcome.checkpoint = Some(WalCheckpoint {
+        busy: busy != 0,
+        log_frames,
+        checkpointed_frames,
+    });
+
+    // 不是 WAL 模式時兩個值都是 -1
+    Ok(if log_frames < 0 {
+        "資料庫不是 WAL 模式，不需要檢查點".to_string()
+    } else if busy != 0 {
+        format!("檢查點未完成（資料庫忙碌），已寫回 {checkpointed_frames}/{log_frames} 個頁框")
+    } else {
+        format!("檢查點完成，已寫回 {checkpointed_frames}/{log_frames} 個頁框")
+    })
+}
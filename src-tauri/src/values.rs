@@ -0,0 +1,116 @@
+use serde_json::Value;
+use sqlx::{Column, Row, TypeInfo, ValueRef};
+
+use crate::binary::binary_value;
+use crate::pg_values;
+use crate::spatial::{self, GeometryFormat};
+
+// 將資料庫的單元格轉換為 JSON 值，解碼失敗時返回 null
+fn cell<T>(decoded: Result<Option<T>, sqlx::Error>, convert: impl FnOnce(T) -> Value) -> Value {
+    match decoded {
+        Ok(Some(value)) => convert(value),
+        Ok(None) => Value::Null,
+        Err(_) => Value::Null,
+    }
+}
+
+fn float_value(n: f64) -> Value {
+    serde_json::Number::from_f64(n)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+pub(crate) fn sqlite_row_values(row: &sqlx::sqlite::SqliteRow) -> Vec<Value> {
+    sqlite_values(row, GeometryFormat::GeoJson)
+}
+
+// 匯出與傾印使用，幾何保留原本的格式才能還原
+pub(crate) fn sqlite_export_values(row: &sqlx::sqlite::SqliteRow) -> Vec<Value> {
+    sqlite_values(row, GeometryFormat::Native)
+}
+
+fn sqlite_values(row: &sqlx::sqlite::SqliteRow, geometry: GeometryFormat) -> Vec<Value> {
+    let mut values = Vec::with_capacity(row.columns().len());
+    for i in 0..row.columns().len() {
+        // SQLite 為動態型別，依據實際儲存類別而非宣告型別解碼
+        let storage_class = match row.try_get_raw(i) {
+            Ok(raw) => raw.type_info().name().to_string(),
+            Err(_) => "NULL".to_string(),
+        };
+
+        let value = match storage_class.as_str() {
+            "NULL" => Value::Null,
+            "INTEGER" => cell(row.try_get::<Option<i64>, _>(i), |n| Value::Number(n.into())),
+            "REAL" => cell(row.try_get::<Option<f64>, _>(i), float_value),
+            // SpatiaLite 幾何轉為 GeoJSON
+            "BLOB" => cell(row.try_get::<Option<Vec<u8>>, _>(i), |bytes| match geometry {
+                GeometryFormat::GeoJson => spatial::spatialite_geojson(&bytes).unwrap_or_else(|| binary_value(&bytes)),
+                GeometryFormat::Native => binary_value(&bytes),
+            }),
+            // 其他類型嘗試轉為字符串
+            _ => cell(row.try_get_unchecked::<Option<String>, _>(i), Value::String),
+        };
+        values.push(value);
+    }
+    values
+}
+
+pub(crate) fn mysql_row_values(row: &sqlx::mysql::MySqlRow) -> Vec<Value> {
+    mysql_values(row, GeometryFormat::GeoJson)
+}
+
+pub(crate) fn mysql_export_values(row: &sqlx::mysql::MySqlRow) -> Vec<Value> {
+    mysql_values(row, GeometryFormat::Native)
+}
+
+fn mysql_values(row: &sqlx::mysql::MySqlRow, geometry: GeometryFormat) -> Vec<Value> {
+    use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+    use sqlx::types::BigDecimal;
+
+    let mut values = Vec::with_capacity(row.columns().len());
+    for (i, column) in row.columns().iter().enumerate() {
+        let value = match column.type_info().name() {
+            "BOOLEAN" => cell(row.try_get::<Option<bool>, _>(i), Value::Bool),
+            "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" => {
+                cell(row.try_get::<Option<i64>, _>(i), |n| Value::Number(n.into()))
+            }
+            "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED" | "BIGINT UNSIGNED" => {
+                cell(row.try_get::<Option<u64>, _>(i), |n| Value::Number(n.into()))
+            }
+            "YEAR" => cell(row.try_get_unchecked::<Option<u16>, _>(i), |n| Value::Number(n.into())),
+            "FLOAT" => cell(row.try_get::<Option<f32>, _>(i), |n| float_value(n as f64)),
+            "DOUBLE" => cell(row.try_get::<Option<f64>, _>(i), float_value),
+            // DECIMAL 以字符串保留完整精度
+            "DECIMAL" => cell(row.try_get::<Option<BigDecimal>, _>(i), |d| Value::String(d.to_string())),
+            "DATE" => cell(row.try_get::<Option<NaiveDate>, _>(i), |d| Value::String(d.to_string())),
+            "DATETIME" => cell(row.try_get::<Option<NaiveDateTime>, _>(i), |d| Value::String(d.to_string())),
+            "TIMESTAMP" => cell(row.try_get::<Option<DateTime<Utc>>, _>(i), |d| Value::String(d.to_rfc3339())),
+            // MySQL TIME 可以是負數或超過 24 小時
+            "TIME" => cell(row.try_get::<Option<sqlx::mysql::types::MySqlTime>, _>(i), |t| Value::String(t.to_string())),
+            "JSON" => cell(row.try_get::<Option<serde_json::Value>, _>(i), |json| json),
+            "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" => {
+                cell(row.try_get::<Option<Vec<u8>>, _>(i), |bytes| binary_value(&bytes))
+            }
+            // 無法解析的幾何仍保留原始資料
+            "GEOMETRY" => cell(row.try_get_unchecked::<Option<Vec<u8>>, _>(i), |bytes| {
+                let value = match geometry {
+                    GeometryFormat::GeoJson => spatial::mysql_geojson(&bytes),
+                    GeometryFormat::Native => spatial::mysql_geometry_value(&bytes),
+                };
+                value.unwrap_or_else(|| binary_value(&bytes))
+            }),
+            _ => cell(row.try_get_unchecked::<Option<String>, _>(i), Value::String),
+        };
+        values.push(value);
+    }
+    values
+}
+
+// PostgreSQL 的型別較多（陣列、範圍、列舉、網路位址等），由 pg_values 依二進位格式解碼
+pub(crate) fn postgres_row_values(row: &sqlx::postgres::PgRow) -> Vec<Value> {
+    (0..row.columns().len()).map(|i| pg_values::postgres_value(row, i, GeometryFormat::GeoJson)).collect()
+}
+
+pub(crate) fn postgres_export_values(row: &sqlx::postgres::PgRow) -> Vec<Value> {
+    (0..row.columns().len()).map(|i| pg_values::postgres_value(row, i, GeometryFormat::Native)).collect()
+}
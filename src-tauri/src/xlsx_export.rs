@@ -0,0 +1,218 @@
+use std::path::PathBuf;
+
+use rust_xlsxwriter::{Color, ExcelDateTime, Format, FormatBorder, Workbook, XlsxError};
+use serde_json::Value;
+use sqlx::types::chrono::DateTime;
+
+use crate::export::{value_text, ExportWriter};
+use crate::query::ResultColumn;
+
+// Excel 單一工作表的行數上限（含標題列）
+const MAX_SHEET_ROWS: u32 = 1_048_576;
+// Excel 單元格的字符數上限
+const MAX_CELL_CHARS: usize = 32_767;
+
+// 每個結果集寫入一個工作表，超過行數上限時自動接續到新工作表
+pub(crate) struct XlsxWriter {
+    path: PathBuf,
+    workbook: Workbook,
+    header_format: Format,
+    date_format: Format,
+    datetime_format: Format,
+    columns: Vec<ResultColumn>,
+    result_index: usize,
+    sheet_part: usize,
+    sheet_count: usize,
+    next_row: u32,
+}
+
+impl XlsxWriter {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            workbook: Workbook::new(),
+            header_format: Format::new()
+                .set_bold()
+                .set_background_color(Color::RGB(0xD9E1F2))
+                .set_border_bottom(FormatBorder::Thin),
+            date_format: Format::new().set_num_format("yyyy-mm-dd"),
+            datetime_format: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"),
+            columns: vec![],
+            result_index: 0,
+            sheet_part: 0,
+            sheet_count: 0,
+            next_row: 0,
+        }
+    }
+
+    fn add_sheet(&mut self) -> Result<(), XlsxError> {
+        self.sheet_part += 1;
+        let name = if self.sheet_part == 1 {
+            format!("結果 {}", self.result_index)
+        } else {
+            format!("結果 {} ({})", self.result_index, self.sheet_part)
+        };
+
+        // 使用固定記憶體模式，寫過的行會直接落地到暫存檔
+        let worksheet = self.workbook.add_worksheet_with_constant_memory();
+        worksheet.set_name(name)?;
+        worksheet.set_freeze_panes(1, 0)?;
+
+        for (col, column) in self.columns.iter().enumerate() {
+            worksheet.write_string_with_format(0, col as u16, &column.name, &self.header_format)?;
+            worksheet.set_column_width(col as u16, column.name.chars().count().clamp(10, 40) as f64)?;
+        }
+
+        self.sheet_count += 1;
+        self.next_row = 1;
+        Ok(())
+    }
+
+    fn write_cells(&mut self, values: &[Value]) -> Result<(), XlsxError> {
+        if self.next_row >= MAX_SHEET_ROWS {
+            self.add_sheet()?;
+        }
+
+        let row = self.next_row;
+        let sheet_index = self.sheet_count - 1;
+        let worksheet = self.workbook.worksheet_from_index(sheet_index)?;
+
+        for (col, value) in values.iter().enumerate() {
+            let col = col as u16;
+            let type_name = self.columns.get(col as usize).map(|column| column.type_name.as_str()).unwrap_or("");
+
+            match value {
+                Value::Null => {}
+                Value::Bool(b) => {
+                    worksheet.write_boolean(row, col, *b)?;
+                }
+                // 超過 Excel 數字精度的整數以文字保存
+                Value::Number(n) if n.as_i64().is_some_and(|i| i.unsigned_abs() > (1u64 << 53)) => {
+                    worksheet.write_string(row, col, n.to_string())?;
+                }
+                Value::Number(n) => {
+                    worksheet.write_number(row, col, n.as_f64().unwrap_or_default())?;
+                }
+                Value::String(s) => match cell_kind(type_name) {
+                    CellKind::Date => match ExcelDateTime::parse_from_str(s) {
+                        Ok(date) => {
+                            worksheet.write_datetime_with_format(row, col, &date, &self.date_format)?;
+                        }
+                        Err(_) => {
+                            worksheet.write_string(row, col, s)?;
+                        }
+                    },
+                    CellKind::DateTime => match ExcelDateTime::parse_from_str(&excel_datetime_text(s)) {
+                        Ok(datetime) => {
+                            worksheet.write_datetime_with_format(row, col, &datetime, &self.datetime_format)?;
+                        }
+                        Err(_) => {
+                            worksheet.write_string(row, col, s)?;
+                        }
+                    },
+                    CellKind::Decimal => match s.parse::<f64>() {
+                        Ok(n) => {
+                            worksheet.write_number(row, col, n)?;
+                        }
+                        Err(_) => {
+                            worksheet.write_string(row, col, s)?;
+                        }
+                    },
+                    CellKind::Text => {
+                        worksheet.write_string(row, col, truncate_cell(s))?;
+                    }
+                },
+                other => {
+                    worksheet.write_string(row, col, truncate_cell(&value_text(other)))?;
+                }
+            }
+        }
+
+        self.next_row += 1;
+        Ok(())
+    }
+}
+
+enum CellKind {
+    Date,
+    DateTime,
+    Decimal,
+    Text,
+}
+
+fn cell_kind(type_name: &str) -> CellKind {
+    match type_name {
+        "DATE" => CellKind::Date,
+        "DATETIME" | "TIMESTAMP" | "TIMESTAMPTZ" => CellKind::DateTime,
+        "DECIMAL" | "NUMERIC" => CellKind::Decimal,
+        _ => CellKind::Text,
+    }
+}
+
+// Excel 沒有時區，保留當地時間並去掉時區（RFC 3339 或 PostgreSQL 的 -05、+05:30）
+fn excel_datetime_text(text: &str) -> String {
+    let text = text.replacen('T', " ", 1);
+    let with_offset = DateTime::parse_from_rfc3339(&text.replacen(' ', "T", 1))
+        .or_else(|_| DateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f%#z"));
+    match with_offset {
+        Ok(datetime) => datetime.naive_local().format("%Y-%m-%d %H:%M:%S%.f").to_string(),
+        Err(_) => text.trim_end_matches('Z').to_string(),
+    }
+}
+
+fn truncate_cell(text: &str) -> &str {
+    match text.char_indices().nth(MAX_CELL_CHARS) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
+
+fn xlsx_error(error: XlsxError) -> std::io::Error {
+    std::io::Error::other(error.to_string())
+}
+
+impl ExportWriter for XlsxWriter {
+    fn begin(&mut self, columns: &[ResultColumn]) -> std::io::Result<()> {
+        self.columns = columns.to_vec();
+        self.result_index += 1;
+        self.sheet_part = 0;
+
+        // 沒有欄位的語句（例如 SET）不建立工作表
+        if columns.is_empty() {
+            return Ok(());
+        }
+        self.add_sheet().map_err(xlsx_error)
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> std::io::Result<()> {
+        self.write_cells(values).map_err(xlsx_error)
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        if self.sheet_count == 0 {
+            self.workbook.add_worksheet();
+        }
+        self.workbook.save(&self.path).map_err(xlsx_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn datetime_text_drops_offsets() {
+        let cases = [
+            ("2024-01-01T10:00:00+08:00", "2024-01-01 10:00:00"),
+            ("2024-01-01T10:00:00-05:00", "2024-01-01 10:00:00"),
+            ("2024-01-01T10:00:00.25Z", "2024-01-01 10:00:00.250"),
+            ("2024-01-01 10:00:00-05", "2024-01-01 10:00:00"),
+            ("2024-01-01 10:00:00+05:30", "2024-01-01 10:00:00"),
+            ("2024-01-01 10:00:00", "2024-01-01 10:00:00"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(excel_datetime_text(text), expected, "{text}");
+            assert!(ExcelDateTime::parse_from_str(&excel_datetime_text(text)).is_ok(), "{text}");
+        }
+    }
+}